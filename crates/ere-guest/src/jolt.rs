@@ -0,0 +1,10 @@
+// The tracer reports the cycles between these markers, which `ere-jolt` adds
+// up per region.
+
+pub(crate) fn cycle_region_start(name: &str) {
+    jolt::start_cycle_tracking(name);
+}
+
+pub(crate) fn cycle_region_end(name: &str) {
+    jolt::end_cycle_tracking(name);
+}
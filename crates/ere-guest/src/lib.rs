@@ -0,0 +1,123 @@
+//! Guest-side I/O that is the same on every zkVM supported by Ere.
+//!
+//! Guests read the items of the host-side `Input` in the order they were
+//! written, commit public values that the host decodes with its
+//! `PublicValuesReader`, and mark the cycle-tracking regions reported in
+//! `ProgramExecutionReport::region_cycles`. Exactly one of the `sp1`, `risc0`,
+//! `openvm`, `pico`, `zisk` and `jolt` features has to be enabled, matching the
+//! zkVM the guest is compiled for.
+//!
+//! Jolt guests receive their inputs as the arguments of their
+//! `#[jolt::provable]` function and return their public values from it, so only
+//! the cycle-tracking regions are available with the `jolt` feature, which
+//! builds the crate as `no_std` like the Jolt guests.
+
+#![cfg_attr(feature = "jolt", no_std)]
+
+#[cfg(not(feature = "jolt"))]
+use serde::{de::DeserializeOwned, Serialize};
+
+const _: () = assert!(
+    cfg!(feature = "sp1") as u8
+        + cfg!(feature = "risc0") as u8
+        + cfg!(feature = "openvm") as u8
+        + cfg!(feature = "pico") as u8
+        + cfg!(feature = "zisk") as u8
+        + cfg!(feature = "jolt") as u8
+        == 1,
+    "enable exactly one of the `sp1`, `risc0`, `openvm`, `pico`, `zisk` and `jolt` features"
+);
+
+#[cfg(feature = "sp1")]
+mod sp1;
+#[cfg(feature = "sp1")]
+use sp1 as imp;
+
+#[cfg(feature = "risc0")]
+mod risc0;
+#[cfg(feature = "risc0")]
+use risc0 as imp;
+
+#[cfg(feature = "openvm")]
+mod openvm;
+#[cfg(feature = "openvm")]
+use openvm as imp;
+
+#[cfg(feature = "pico")]
+mod pico;
+#[cfg(feature = "pico")]
+use pico as imp;
+
+#[cfg(feature = "zisk")]
+mod zisk;
+#[cfg(feature = "zisk")]
+use zisk as imp;
+
+#[cfg(feature = "jolt")]
+mod jolt;
+#[cfg(feature = "jolt")]
+use jolt as imp;
+
+/// Reads the next input item, written by the host with `Input::write`.
+///
+/// # Panics
+///
+/// Panics if every item has been read, or the next one does not decode as `T`.
+#[cfg(not(feature = "jolt"))]
+pub fn read<T: DeserializeOwned>() -> T {
+    imp::read()
+}
+
+/// Reads the next input item as raw bytes, written by the host with
+/// `Input::write_bytes`.
+///
+/// # Panics
+///
+/// Panics if every item has been read.
+#[cfg(not(feature = "jolt"))]
+pub fn read_bytes() -> Vec<u8> {
+    imp::read_bytes()
+}
+
+/// Commits `value` to the public values, after the ones committed before.
+///
+/// # Panics
+///
+/// Panics if `value` cannot be serialized, e.g. a sequence of unknown length.
+#[cfg(not(feature = "jolt"))]
+pub fn commit<T: Serialize>(value: &T) {
+    imp::commit(value)
+}
+
+/// Commits `value` to the output slots after the ones written before, with
+/// `write(slot, word)` writing each `u32` slot once. The `bincode` encoding of
+/// `value` is padded with zeros to whole words, as decoded by the host with
+/// `PublicValuesEncoding::WordAlignedBincode`.
+#[cfg(any(feature = "openvm", feature = "zisk"))]
+fn commit_words<T: Serialize>(value: &T, write: impl Fn(usize, u32)) {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Index of the next output slot to write.
+    static NEXT_SLOT: AtomicUsize = AtomicUsize::new(0);
+
+    let mut bytes = bincode::serialize(value).expect("failed to serialize public value");
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+    let first_slot = NEXT_SLOT.fetch_add(bytes.len() / 4, Ordering::Relaxed);
+    for (slot, word) in (first_slot..).zip(bytes.chunks_exact(4)) {
+        write(slot, u32::from_le_bytes(word.try_into().unwrap()));
+    }
+}
+
+/// Starts the cycle-tracking region `name`.
+///
+/// SP1 and Jolt report the cycles spent in the region in
+/// `ProgramExecutionReport::region_cycles`, while the other zkVMs do not track
+/// regions and ignore it.
+pub fn cycle_region_start(name: &str) {
+    imp::cycle_region_start(name)
+}
+
+/// Ends the cycle-tracking region `name`, started by [`cycle_region_start`].
+pub fn cycle_region_end(name: &str) {
+    imp::cycle_region_end(name)
+}
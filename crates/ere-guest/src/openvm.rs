@@ -0,0 +1,19 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(crate) fn read<T: DeserializeOwned>() -> T {
+    openvm::io::read()
+}
+
+pub(crate) fn read_bytes() -> Vec<u8> {
+    openvm::io::read_vec()
+}
+
+pub(crate) fn commit<T: Serialize>(value: &T) {
+    // OpenVM reveals public values word by word at a given index, and every
+    // index is revealed at most once.
+    crate::commit_words(value, |index, word| openvm::io::reveal_u32(word, index));
+}
+
+pub(crate) fn cycle_region_start(_name: &str) {}
+
+pub(crate) fn cycle_region_end(_name: &str) {}
@@ -0,0 +1,18 @@
+use risc0_zkvm::guest::env;
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(crate) fn read<T: DeserializeOwned>() -> T {
+    env::read()
+}
+
+pub(crate) fn read_bytes() -> Vec<u8> {
+    env::read_frame()
+}
+
+pub(crate) fn commit<T: Serialize>(value: &T) {
+    env::commit(value)
+}
+
+pub(crate) fn cycle_region_start(_name: &str) {}
+
+pub(crate) fn cycle_region_end(_name: &str) {}
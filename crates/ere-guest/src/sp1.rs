@@ -0,0 +1,24 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(crate) fn read<T: DeserializeOwned>() -> T {
+    sp1_zkvm::io::read()
+}
+
+pub(crate) fn read_bytes() -> Vec<u8> {
+    sp1_zkvm::io::read_vec()
+}
+
+pub(crate) fn commit<T: Serialize>(value: &T) {
+    sp1_zkvm::io::commit(value)
+}
+
+// The executor adds up the cycles between these markers into the cycle tracker
+// of its `ExecutionReport`.
+
+pub(crate) fn cycle_region_start(name: &str) {
+    println!("cycle-tracker-report-start: {name}");
+}
+
+pub(crate) fn cycle_region_end(name: &str) {
+    println!("cycle-tracker-report-end: {name}");
+}
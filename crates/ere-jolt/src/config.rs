@@ -0,0 +1,78 @@
+use jolt_core::host::Program;
+use serde::Deserialize;
+
+/// Memory layout and trace bounds of a Jolt guest.
+///
+/// Read from the `[package.metadata.jolt]` table of the guest manifest by
+/// [`JOLT_TARGET::compile`](crate::JOLT_TARGET), with the Jolt SDK defaults for
+/// any missing key. The sizes have to agree with the arguments of the
+/// `#[jolt::provable]` function of the guest, e.g.
+///
+/// ```toml
+/// [package.metadata.jolt]
+/// max_input_size = 1048576
+/// memory_size = 104857600
+/// max_trace_length = 16777216
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JoltConfig {
+    /// Size in bytes of the region the serialized inputs are written to.
+    pub max_input_size: u64,
+    /// Size in bytes of the region the serialized outputs are written to.
+    pub max_output_size: u64,
+    /// Size in bytes of the guest stack.
+    pub stack_size: u64,
+    /// Size in bytes of the guest memory, including the heap.
+    pub memory_size: u64,
+    /// Maximum number of bytecode instructions, used for preprocessing.
+    pub max_bytecode_size: usize,
+    /// Maximum number of memory addresses, used for preprocessing.
+    pub max_memory_size: usize,
+    /// Maximum number of cycles of an execution that can be proven.
+    pub max_trace_length: usize,
+}
+
+impl Default for JoltConfig {
+    fn default() -> Self {
+        Self {
+            max_input_size: 4096,
+            max_output_size: 4096,
+            stack_size: 4096,
+            memory_size: 10 * 1024 * 1024,
+            max_bytecode_size: 1 << 20,
+            max_memory_size: 1 << 20,
+            max_trace_length: 1 << 24,
+        }
+    }
+}
+
+impl JoltConfig {
+    /// Sets the memory layout on the program, which has to happen before it is
+    /// built since the stack and memory sizes are linked into the ELF.
+    pub(crate) fn apply(&self, program: &mut Program) {
+        program.set_max_input_size(self.max_input_size);
+        program.set_max_output_size(self.max_output_size);
+        program.set_stack_size(self.stack_size);
+        program.set_memory_size(self.memory_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JoltConfig;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: JoltConfig = toml::from_str("max_input_size = 1048576").unwrap();
+        assert_eq!(
+            config,
+            JoltConfig {
+                max_input_size: 1 << 20,
+                ..Default::default()
+            }
+        );
+
+        assert!(toml::from_str::<JoltConfig>("max_input = 1").is_err());
+    }
+}
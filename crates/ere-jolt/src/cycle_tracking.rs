@@ -0,0 +1,199 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use indexmap::IndexMap;
+use tracing::{
+    Dispatch, Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+};
+
+/// Runs `f`, collecting the cycles spent between every
+/// `jolt::start_cycle_tracking` and `jolt::end_cycle_tracking` pair the guest
+/// hits while being traced.
+///
+/// The Jolt tracer does not return these, it only logs a
+/// `"<label>": <n> RV32IM cycles, <m> virtual cycles` event when a region ends,
+/// so they are picked up from the log on the current thread. Everything is
+/// still passed on to the subscriber the host has set up, which sees the same
+/// events and spans as without it. Regions are reported in virtual cycles, the
+/// same unit as the trace length, and summed when a label is tracked more than
+/// once.
+pub(crate) fn with_region_cycles<T>(f: impl FnOnce() -> T) -> (T, IndexMap<String, u64>) {
+    let regions = Arc::new(Mutex::new(IndexMap::new()));
+    let subscriber = CycleMarkers {
+        inner: tracing::dispatcher::get_default(Dispatch::clone),
+        regions: regions.clone(),
+    };
+
+    let result = tracing::subscriber::with_default(subscriber, f);
+
+    let regions = std::mem::take(&mut *regions.lock().unwrap());
+    (result, regions)
+}
+
+/// Subscriber that picks up the cycle markers from the events and forwards
+/// everything to the subscriber it wraps.
+struct CycleMarkers {
+    inner: Dispatch,
+    regions: Arc<Mutex<IndexMap<String, u64>>>,
+}
+
+impl CycleMarkers {
+    /// The markers are logged at `INFO`, so events up to that level are seen
+    /// even when the wrapped subscriber filters them out.
+    fn is_marker_candidate(metadata: &Metadata<'_>) -> bool {
+        metadata.is_event() && *metadata.level() <= Level::INFO
+    }
+}
+
+impl Subscriber for CycleMarkers {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // Interest is cached per callsite across subscribers, so let `enabled`
+        // decide every time instead.
+        let _ = self.inner.register_callsite(metadata);
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        Self::is_marker_candidate(metadata) || self.inner.enabled(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        None
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        self.inner.new_span(span)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        self.inner.record(span, values)
+    }
+
+    fn record_follows_from(&self, span: &Id, follows: &Id) {
+        self.inner.record_follows_from(span, follows)
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        if Self::is_marker_candidate(event.metadata()) {
+            let mut visitor = MessageVisitor(None);
+            event.record(&mut visitor);
+            if let Some((label, cycles)) = visitor.0.as_deref().and_then(parse_cycle_marker) {
+                *self.regions.lock().unwrap().entry(label).or_default() += cycles;
+            }
+        }
+        if self.inner.enabled(event.metadata()) {
+            self.inner.event(event);
+        }
+    }
+
+    fn enter(&self, span: &Id) {
+        self.inner.enter(span)
+    }
+
+    fn exit(&self, span: &Id) {
+        self.inner.exit(span)
+    }
+
+    fn clone_span(&self, id: &Id) -> Id {
+        self.inner.clone_span(id)
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        self.inner.try_close(id)
+    }
+}
+
+struct MessageVisitor(Option<String>);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Parses `"<label>": <n> RV32IM cycles, <m> virtual cycles` into the label
+/// and `m`.
+fn parse_cycle_marker(message: &str) -> Option<(String, u64)> {
+    let (label, counts) = message.strip_prefix('"')?.split_once("\": ")?;
+    let (_, virtual_cycles) = counts.split_once(" cycles, ")?;
+    let virtual_cycles = virtual_cycles
+        .strip_suffix(" virtual cycles")?
+        .parse()
+        .ok()?;
+    Some((label.to_string(), virtual_cycles))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{Event, Subscriber, level_filters::LevelFilter};
+    use tracing_subscriber::{Layer, layer::Context, prelude::*};
+
+    use super::{MessageVisitor, parse_cycle_marker, with_region_cycles};
+
+    #[test]
+    fn test_parse_cycle_marker() {
+        assert_eq!(
+            parse_cycle_marker("\"fib_loop\": 120 RV32IM cycles, 150 virtual cycles"),
+            Some(("fib_loop".to_string(), 150))
+        );
+        assert_eq!(parse_cycle_marker("Tracing program"), None);
+    }
+
+    #[test]
+    fn test_with_region_cycles() {
+        let (value, regions) = with_region_cycles(|| {
+            tracing::info!("\"setup\": 1 RV32IM cycles, 2 virtual cycles");
+            tracing::info!("\"compute\": 10 RV32IM cycles, 20 virtual cycles");
+            tracing::info!("\"setup\": 3 RV32IM cycles, 4 virtual cycles");
+            7
+        });
+
+        assert_eq!(value, 7);
+        assert_eq!(
+            regions.into_iter().collect::<Vec<_>>(),
+            [("setup".to_string(), 6), ("compute".to_string(), 20)]
+        );
+    }
+
+    /// Layer of the host, recording the messages of the events it sees.
+    struct RecordMessages(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for RecordMessages {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut visitor = MessageVisitor(None);
+            event.record(&mut visitor);
+            self.0.lock().unwrap().extend(visitor.0);
+        }
+    }
+
+    #[test]
+    fn test_with_region_cycles_keeps_host_subscriber() {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let host = tracing_subscriber::registry()
+            .with(RecordMessages(messages.clone()).with_filter(LevelFilter::WARN));
+
+        let ((), regions) = tracing::subscriber::with_default(host, || {
+            with_region_cycles(|| {
+                tracing::info!("\"compute\": 10 RV32IM cycles, 20 virtual cycles");
+                tracing::warn!("guest is slow");
+            })
+        });
+
+        // The markers are collected although the host filters them out, and the
+        // host still sees the events it lets through.
+        assert_eq!(
+            regions.into_iter().collect::<Vec<_>>(),
+            [("compute".to_string(), 20)]
+        );
+        assert_eq!(*messages.lock().unwrap(), ["guest is slow"]);
+    }
+}
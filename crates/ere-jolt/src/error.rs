@@ -1,8 +1,33 @@
-use zkvm_interface::zkVMError;
+use std::path::{Path, PathBuf};
+
+use ark_serialize::SerializationError;
+use zkvm_interface::{GuestOutput, InputError, zkVMError};
 
 impl From<JoltError> for zkVMError {
     fn from(value: JoltError) -> Self {
-        zkVMError::Other(Box::new(value))
+        if let JoltError::GuestPanicked { output } = &value {
+            return zkVMError::GuestPanicked {
+                exit_code: None,
+                message: output.panic_message(),
+                output: output.clone(),
+                source: Box::new(value),
+            };
+        }
+        match value {
+            err @ JoltError::ProofVerificationFailed => zkVMError::InvalidProof(Box::new(err)),
+            err @ (JoltError::ReadManifest { .. }
+            | JoltError::ParseManifest { .. }
+            | JoltError::MissingPackageName(_)
+            | JoltError::Build { .. }
+            | JoltError::InvalidConfig { .. }
+            | JoltError::Keys(KeysError::MissingElf)) => zkVMError::Compile(Box::new(err)),
+            err @ (JoltError::InputTooLarge { .. }
+            | JoltError::SerializeInput(_)
+            | JoltError::ReadInput(_)) => zkVMError::InvalidInput(Box::new(err)),
+            err @ JoltError::TraceTooLong { .. } => zkVMError::CycleLimitExceeded(Box::new(err)),
+            err @ JoltError::DecodeProof(_) => zkVMError::ProofDecode(Box::new(err)),
+            err => zkVMError::Other(Box::new(err)),
+        }
     }
 }
 
@@ -10,4 +35,89 @@ impl From<JoltError> for zkVMError {
 pub enum JoltError {
     #[error("Proof verification failed")]
     ProofVerificationFailed,
+
+    #[error("Failed to read guest manifest {path}: {source}")]
+    ReadManifest {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse guest manifest {path}: {source}")]
+    ParseManifest {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("Could not find `[package].name` in guest manifest {0}")]
+    MissingPackageName(PathBuf),
+
+    #[error("Failed to build Jolt guest {path}: {message}")]
+    Build { path: PathBuf, message: String },
+
+    #[error("Invalid `[package.metadata.jolt]` in {path}: {source}")]
+    InvalidConfig {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("Serialized input is {size} bytes, but the guest only has room for {max_input_size}")]
+    InputTooLarge { size: u64, max_input_size: u64 },
+
+    #[error("Guest panicked")]
+    GuestPanicked { output: GuestOutput },
+
+    #[error("Failed to capture the output of the guest: {0}")]
+    CaptureOutput(#[source] std::io::Error),
+
+    #[error("Execution took {trace_length} cycles, more than the maximum of {max_trace_length}")]
+    TraceTooLong {
+        trace_length: usize,
+        max_trace_length: usize,
+    },
+
+    #[error("Serialising input with `postcard` failed: {0}")]
+    SerializeInput(#[source] postcard::Error),
+
+    #[error(transparent)]
+    ReadInput(#[from] InputError),
+
+    #[error("Serialising proof failed: {0}")]
+    Proof(#[source] SerializationError),
+
+    #[error("Deserialising proof failed: {0}")]
+    DecodeProof(#[source] SerializationError),
+
+    #[error(transparent)]
+    Keys(#[from] KeysError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeysError {
+    #[error("Failed to access key file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Serialising preprocessing failed: {0}")]
+    Serialization(#[from] SerializationError),
+
+    #[error("Program has not been built, so there is no ELF to take the digest of")]
+    MissingElf,
+
+    #[error("Keys in {0} were generated for a different program")]
+    ProgramMismatch(PathBuf),
+}
+
+impl KeysError {
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
 }
@@ -1,61 +1,135 @@
-use zkvm_interface::Input;
+use std::io::Read;
+
+use indexmap::IndexMap;
+use zkvm_interface::{
+    GuestOutput, Input, InputEncoding, InputError, InputItem, capture_output, open_input_file,
+};
+
+use crate::{JoltConfig, JoltError, cycle_tracking::with_region_cycles};
+
+pub type JoltProverPreprocessing =
+    jolt::JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>;
+
+pub type JoltVerifierPreprocessing =
+    jolt::JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>;
 
 pub fn preprocess_prover(
     program: &jolt::host::Program,
-) -> jolt::JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> {
-    use jolt::{Jolt, JoltProverPreprocessing, MemoryLayout, RV32IJoltVM};
+    config: &JoltConfig,
+) -> JoltProverPreprocessing {
+    use jolt::{Jolt, MemoryLayout, RV32IJoltVM};
+
     let (bytecode, memory_init) = program.decode();
-    let memory_layout = MemoryLayout::new(4096, 4096);
-    let preprocessing: JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> =
-        RV32IJoltVM::prover_preprocess(
-            bytecode,
-            memory_layout,
-            memory_init,
-            1 << 20,
-            1 << 20,
-            1 << 24,
-        );
+    let memory_layout = MemoryLayout::new(config.max_input_size, config.max_output_size);
+    let preprocessing: JoltProverPreprocessing = RV32IJoltVM::prover_preprocess(
+        bytecode,
+        memory_layout,
+        memory_init,
+        config.max_bytecode_size,
+        config.max_memory_size,
+        config.max_trace_length,
+    );
     preprocessing
 }
 
 pub fn preprocess_verifier(
     program: &jolt::host::Program,
-) -> jolt::JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> {
-    use jolt::{Jolt, JoltVerifierPreprocessing, MemoryLayout, RV32IJoltVM};
+    config: &JoltConfig,
+) -> JoltVerifierPreprocessing {
+    use jolt::{Jolt, MemoryLayout, RV32IJoltVM};
 
     let (bytecode, memory_init) = program.decode();
-    let memory_layout = MemoryLayout::new(4096, 4096);
-    let preprocessing: JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> =
-        RV32IJoltVM::verifier_preprocess(
-            bytecode,
-            memory_layout,
-            memory_init,
-            1 << 20,
-            1 << 20,
-            1 << 24,
-        );
+    let memory_layout = MemoryLayout::new(config.max_input_size, config.max_output_size);
+    let preprocessing: JoltVerifierPreprocessing = RV32IJoltVM::verifier_preprocess(
+        bytecode,
+        memory_layout,
+        memory_init,
+        config.max_bytecode_size,
+        config.max_memory_size,
+        config.max_trace_length,
+    );
     preprocessing
 }
 
+/// Serializes the inputs into the input buffer of the guest, the way the
+/// `#[jolt::provable]` macro does: `Object`s are `postcard`-encoded back to
+/// back, `Bytes` are appended as they are.
+///
+/// Fails if the result does not fit the input region of the memory layout.
+pub fn serialize_inputs(inputs: &Input, config: &JoltConfig) -> Result<Vec<u8>, JoltError> {
+    let mut input_bytes = Vec::new();
+    for input in inputs.iter() {
+        match input {
+            InputItem::Object(serialize) => input_bytes
+                .extend(postcard::to_stdvec(serialize).map_err(JoltError::SerializeInput)?),
+            InputItem::Bytes(bytes) => input_bytes.extend_from_slice(bytes),
+            InputItem::File(path) => {
+                // Check the size up front rather than reading an oversized file,
+                // then read it straight into the input.
+                let (mut file, len) = open_input_file(path)?;
+                ensure_input_size(input_bytes.len() as u64 + len, config)?;
+                file.read_to_end(&mut input_bytes)
+                    .map_err(|source| InputError::ReadFile {
+                        path: path.clone(),
+                        source,
+                    })?;
+            }
+            InputItem::Encoded(obj) => {
+                input_bytes.extend_from_slice(obj.encoded(InputEncoding::Postcard))
+            }
+        }
+    }
+    ensure_input_size(input_bytes.len() as u64, config)?;
+    Ok(input_bytes)
+}
+
+fn ensure_input_size(size: u64, config: &JoltConfig) -> Result<(), JoltError> {
+    if size > config.max_input_size {
+        return Err(JoltError::InputTooLarge {
+            size,
+            max_input_size: config.max_input_size,
+        });
+    }
+    Ok(())
+}
+
+/// Traces the program without proving, returning the outputs, the number of
+/// cycles executed and the cycles spent in each cycle-tracking region.
+///
+/// The tracer prints what the guest writes to the stdout of the host, which is
+/// left to the caller to capture, so a [`JoltError::GuestPanicked`] returned
+/// here carries no output.
+pub fn execute_generic(
+    program: &jolt::host::Program,
+    input_bytes: &[u8],
+) -> Result<(Vec<u8>, usize, IndexMap<String, u64>), JoltError> {
+    let (summary, region_cycles) =
+        with_region_cycles(|| program.clone().trace_analyze::<jolt::F>(input_bytes));
+    if summary.io_device.panic {
+        return Err(JoltError::GuestPanicked {
+            output: GuestOutput::default(),
+        });
+    }
+    let trace_len = summary.trace_len();
+    Ok((summary.io_device.outputs, trace_len, region_cycles))
+}
+
+/// Verifies the proof against the given inputs and outputs, which are bound
+/// into the `JoltDevice` the proof is checked against.
 pub fn verify_generic(
     proof: jolt::JoltHyperKZGProof,
-    // TODO: input should be private input
-    _inputs: Input,
-    _outputs: Input,
-    preprocessing: jolt::JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>,
+    inputs: Vec<u8>,
+    outputs: Vec<u8>,
+    preprocessing: JoltVerifierPreprocessing,
 ) -> bool {
     use jolt::{Jolt, RV32IJoltVM, tracer};
 
-    let preprocessing = std::sync::Arc::new(preprocessing);
-    let preprocessing = (*preprocessing).clone();
-    let io_device = tracer::JoltDevice::new(
+    let mut io_device = tracer::JoltDevice::new(
         preprocessing.memory_layout.max_input_size,
         preprocessing.memory_layout.max_output_size,
     );
-
-    // TODO: FIXME
-    // io_device.inputs = inputs.bytes().to_vec();
-    // io_device.outputs = outputs.bytes().to_vec();
+    io_device.inputs = inputs;
+    io_device.outputs = outputs;
 
     RV32IJoltVM::verify(
         preprocessing,
@@ -67,27 +141,40 @@ pub fn verify_generic(
     .is_ok()
 }
 
+/// Proves the program on the serialized inputs, returning the outputs and the
+/// proof.
+///
+/// Fails without proving if the trace is longer than the preprocessing allows.
 pub fn prove_generic(
     program: &jolt::host::Program,
-    preprocessing: jolt::JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>,
-    _inputs: &Input,
-) -> (Vec<u8>, jolt::JoltHyperKZGProof) {
+    preprocessing: &JoltProverPreprocessing,
+    input_bytes: &[u8],
+    config: &JoltConfig,
+) -> Result<(Vec<u8>, jolt::JoltHyperKZGProof), JoltError> {
     use jolt::{Jolt, RV32IJoltVM};
 
     let mut program = program.clone();
+    let ((io_device, trace), output) =
+        capture_output(|| program.trace(input_bytes)).map_err(JoltError::CaptureOutput)?;
 
-    // Convert inputs to a flat vector
-    // TODO: FIXME
-    let input_bytes = Vec::new();
-
-    let (io_device, trace) = program.trace(&input_bytes);
+    if io_device.panic {
+        return Err(JoltError::GuestPanicked { output });
+    }
+    if trace.len() > config.max_trace_length {
+        return Err(JoltError::TraceTooLong {
+            trace_length: trace.len(),
+            max_trace_length: config.max_trace_length,
+        });
+    }
 
+    // The prover takes the preprocessing by value and mutates it, so it is
+    // copied here, only once the trace is known to be provable.
     let (jolt_proof, jolt_commitments, output_io_device, _) =
-        RV32IJoltVM::prove(io_device, trace, preprocessing);
+        RV32IJoltVM::prove(io_device, trace, preprocessing.clone());
 
     let proof = jolt::JoltHyperKZGProof {
         proof: jolt_proof,
         commitments: jolt_commitments,
     };
-    (output_io_device.outputs.clone(), proof)
+    Ok((output_io_device.outputs, proof))
 }
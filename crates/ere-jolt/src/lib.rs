@@ -1,107 +1,306 @@
-use error::JoltError;
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use error::{JoltError, KeysError};
 use jolt_core::host::Program;
-use jolt_methods::{preprocess_prover, preprocess_verifier, prove_generic, verify_generic};
+use jolt_methods::{
+    JoltProverPreprocessing, JoltVerifierPreprocessing, execute_generic, preprocess_prover,
+    preprocess_verifier, prove_generic, serialize_inputs, verify_generic,
+};
 use jolt_sdk::host::DEFAULT_TARGET_DIR;
+use std::time::Instant;
 use utils::{
-    deserialize_public_input_with_proof, package_name_from_manifest,
-    serialize_public_input_with_proof,
+    deserialize_io_with_proof, jolt_config_from_manifest, package_name_from_manifest,
+    panic_message, serialize_io_with_proof,
 };
 use zkvm_interface::{
-    Compiler, Input, ProgramExecutionReport, ProgramProvingReport, ProverResourceType, zkVM,
-    zkVMError,
+    Compiler, ExecutionLimits, Input, InputEncoding, MaxCyclesUnsupported, ProgramDigest,
+    ProgramExecutionReport, ProgramProvingReport, ProofEnvelope, ProofKind, ProverResourceType,
+    PublicValues, PublicValuesEncoding, capture_output, zkVM, zkVMError, zkVMVerifier,
 };
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
+
+/// File name of the program digest written by [`EreJolt::export_keys`].
+const PROGRAM_DIGEST_FILE: &str = "program_digest";
+/// File name of the compressed prover preprocessing.
+const PROVER_PREPROCESSING_FILE: &str = "prover_preprocessing.bin";
+/// File name of the compressed verifier preprocessing.
+const VERIFIER_PREPROCESSING_FILE: &str = "verifier_preprocessing.bin";
+mod config;
+mod cycle_tracking;
 mod error;
 mod jolt_methods;
 mod utils;
 
+pub use config::JoltConfig;
+
 #[allow(non_camel_case_types)]
 pub struct JOLT_TARGET;
 
 impl Compiler for JOLT_TARGET {
     type Error = JoltError;
 
-    type Program = Program;
+    type Program = JoltProgram;
 
     fn compile(path_to_program: &std::path::Path) -> Result<Self::Program, Self::Error> {
         let manifest_path = path_to_program.to_path_buf().join("Cargo.toml");
-        let package_name = package_name_from_manifest(&manifest_path).unwrap();
+        let package_name = package_name_from_manifest(&manifest_path)?;
+        let config = jolt_config_from_manifest(&manifest_path)?;
         let mut program = Program::new(&package_name);
         program.set_std(true);
         program.set_manifest_path(manifest_path);
+        config.apply(&mut program);
 
-        // TODO: Note that if this fails, it will panic which is why it doesn't return a Result.
-        program.build(DEFAULT_TARGET_DIR);
+        // `Program::build` panics instead of returning an error if the guest
+        // fails to build.
+        panic::catch_unwind(AssertUnwindSafe(|| program.build(DEFAULT_TARGET_DIR))).map_err(
+            |payload| JoltError::Build {
+                path: path_to_program.to_path_buf(),
+                message: panic_message(payload),
+            },
+        )?;
 
-        Ok(program)
+        Ok(JoltProgram { program, config })
     }
 }
 
+/// A compiled Jolt guest, together with the memory layout it was built with.
+#[derive(Clone)]
+pub struct JoltProgram {
+    pub program: Program,
+    pub config: JoltConfig,
+}
+
 pub struct EreJolt {
     program: <JOLT_TARGET as Compiler>::Program,
+    prover_preprocessing: JoltProverPreprocessing,
+    verifier_preprocessing: JoltVerifierPreprocessing,
+    /// blake3 hash of the program ELF.
+    program_id: ProgramDigest,
 }
 
 impl EreJolt {
+    /// Preprocesses the program for both the prover and the verifier once, so
+    /// the preprocessing is reused by every later call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the program ELF cannot be read, see [`EreJolt::try_new`].
     pub fn new(
         program: <JOLT_TARGET as Compiler>::Program,
-        _resource_type: ProverResourceType,
+        resource_type: ProverResourceType,
     ) -> Self {
-        EreJolt { program }
+        Self::try_new(program, resource_type).expect("Failed to hash the Jolt program ELF")
     }
+
+    /// Like [`EreJolt::new`], but returns an error instead of panicking if the
+    /// program has not been built or its ELF cannot be read.
+    pub fn try_new(
+        program: <JOLT_TARGET as Compiler>::Program,
+        _resource_type: ProverResourceType,
+    ) -> Result<Self, JoltError> {
+        let program_id = program_digest(&program)?;
+        let prover_preprocessing = preprocess_prover(&program.program, &program.config);
+        let verifier_preprocessing = preprocess_verifier(&program.program, &program.config);
+        Ok(EreJolt {
+            program,
+            prover_preprocessing,
+            verifier_preprocessing,
+            program_id,
+        })
+    }
+
+    /// Returns the compressed verifier preprocessing, from which an
+    /// [`EreJoltVerifier`] can be created.
+    pub fn verifier_preprocessing_bytes(&self) -> Result<Vec<u8>, JoltError> {
+        let mut verifier_preprocessing = Vec::new();
+        self.verifier_preprocessing
+            .serialize_compressed(&mut verifier_preprocessing)
+            .map_err(KeysError::from)?;
+        Ok(verifier_preprocessing)
+    }
+
+    /// Writes the prover and verifier preprocessing to `dir`, alongside the
+    /// digest of the program ELF, so that [`EreJolt::load_keys`] can skip
+    /// preprocessing next time.
+    pub fn export_keys(&self, dir: &Path) -> Result<(), JoltError> {
+        fs::create_dir_all(dir).map_err(|source| KeysError::io(dir, source))?;
+        write_key_file(&dir.join(PROGRAM_DIGEST_FILE), self.program_id.as_bytes())?;
+
+        let mut prover_preprocessing = Vec::new();
+        self.prover_preprocessing
+            .serialize_compressed(&mut prover_preprocessing)
+            .map_err(KeysError::from)?;
+        write_key_file(&dir.join(PROVER_PREPROCESSING_FILE), &prover_preprocessing)?;
+
+        write_key_file(
+            &dir.join(VERIFIER_PREPROCESSING_FILE),
+            &self.verifier_preprocessing_bytes()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Creates an instance from preprocessing previously written by
+    /// [`EreJolt::export_keys`] instead of preprocessing the program again.
+    ///
+    /// Preprocessing that was generated for a different program is rejected.
+    pub fn load_keys(
+        program: <JOLT_TARGET as Compiler>::Program,
+        _resource_type: ProverResourceType,
+        dir: &Path,
+    ) -> Result<Self, JoltError> {
+        let program_id = program_digest(&program)?;
+        let digest = read_key_file(&dir.join(PROGRAM_DIGEST_FILE))?;
+        if digest != program_id.as_bytes() {
+            return Err(KeysError::ProgramMismatch(dir.to_path_buf()).into());
+        }
+
+        let prover_preprocessing = JoltProverPreprocessing::deserialize_compressed(
+            read_key_file(&dir.join(PROVER_PREPROCESSING_FILE))?.as_slice(),
+        )
+        .map_err(KeysError::from)?;
+        let verifier_preprocessing = JoltVerifierPreprocessing::deserialize_compressed(
+            read_key_file(&dir.join(VERIFIER_PREPROCESSING_FILE))?.as_slice(),
+        )
+        .map_err(KeysError::from)?;
+
+        Ok(EreJolt {
+            program,
+            prover_preprocessing,
+            verifier_preprocessing,
+            program_id,
+        })
+    }
+}
+
+/// Digest of the compiled ELF the preprocessing was generated for.
+fn program_digest(program: &JoltProgram) -> Result<ProgramDigest, KeysError> {
+    let elf_path = program.program.elf.as_ref().ok_or(KeysError::MissingElf)?;
+    Ok(ProgramDigest::new(
+        *blake3::hash(&read_key_file(elf_path)?).as_bytes(),
+    ))
+}
+
+fn write_key_file(path: &Path, contents: &[u8]) -> Result<(), KeysError> {
+    fs::write(path, contents).map_err(|source| KeysError::io(path, source))
+}
+
+fn read_key_file(path: &Path) -> Result<Vec<u8>, KeysError> {
+    fs::read(path).map_err(|source| KeysError::io(path, source))
 }
 impl zkVM for EreJolt {
-    fn execute(
+    fn execute_with_limits(
         &self,
-        _inputs: &Input,
-    ) -> Result<zkvm_interface::ProgramExecutionReport, zkVMError> {
-        // TODO: check ProgramSummary
-        // TODO: FIXME
-        // let summary = self
-        //     .program
-        //     .clone()
-        //     .trace_analyze::<jolt::F>(inputs.bytes());
-        // let trace_len = summary.trace_len();
-        let trace_len = 0;
+        inputs: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, ProgramExecutionReport), zkVMError> {
+        if limits.max_cycles.is_some() {
+            return Err(zkVMError::Unsupported(Box::new(MaxCyclesUnsupported {
+                zkvm: NAME,
+            })));
+        }
+        let input_bytes = serialize_inputs(inputs, &self.program.config)?;
+
+        let program = self.program.program.clone();
+        let start = Instant::now();
+        // The tracer has no way to be stopped, nor to be bounded by a number of
+        // cycles. On timeout the worker thread is detached and keeps tracing
+        // until the guest halts, which for a guest that never halts is never.
+        // The output is captured around the deadline rather than inside it, so
+        // that the capture ends with the deadline, and whatever the guest prints
+        // after that goes to the stdout of the host.
+        let (result, guest_output) = capture_output(|| {
+            limits.run_with_deadline(move || execute_generic(&program, &input_bytes))
+        })
+        .map_err(JoltError::CaptureOutput)?;
+        let (output_bytes, trace_len, region_cycles) = result?.map_err(|mut err| {
+            if let JoltError::GuestPanicked { output } = &mut err {
+                *output = guest_output.clone();
+            }
+            err
+        })?;
+        let report = ProgramExecutionReport {
+            total_num_cycles: trace_len as u64,
+            region_cycles,
+            execution_duration: start.elapsed(),
+            guest_output,
+        };
 
-        Ok(ProgramExecutionReport::new(trace_len as u64))
+        Ok((output_bytes, report))
     }
 
-    fn prove(
+    fn prove_with_limits(
         &self,
         inputs: &Input,
-    ) -> Result<(Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
-        // TODO: make this stateful and do in setup since its expensive and should be done once per program;
-        let preprocessed_key = preprocess_prover(&self.program);
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
+        if proof_kind != ProofKind::Core {
+            return Err(zkVMError::UnsupportedProofKind {
+                zkvm: NAME,
+                proof_kind,
+            });
+        }
+
+        if !limits.is_unlimited() {
+            // Tracing is cheap next to proving, so check the limits on a separate
+            // trace before the prover traces the program again.
+            self.execute_with_limits(inputs, limits)?;
+        }
 
-        let now = std::time::Instant::now();
-        let (output_bytes, proof) = prove_generic(&self.program, preprocessed_key, inputs);
+        let input_bytes = serialize_inputs(inputs, &self.program.config)?;
+
+        let now = Instant::now();
+        let (output_bytes, proof) = prove_generic(
+            &self.program.program,
+            &self.prover_preprocessing,
+            &input_bytes,
+            &self.program.config,
+        )?;
         let elapsed = now.elapsed();
 
-        let proof_with_public_inputs =
-            serialize_public_input_with_proof(&output_bytes, &proof).unwrap();
+        let proof_with_io = serialize_io_with_proof(&input_bytes, &output_bytes, &proof)
+            .map_err(JoltError::Proof)?;
+
+        let envelope = ProofEnvelope::new(
+            NAME,
+            SDK_VERSION,
+            self.program_id(),
+            proof_kind,
+            proof_with_io,
+        );
 
-        Ok((proof_with_public_inputs, ProgramProvingReport::new(elapsed)))
+        Ok((
+            output_bytes,
+            envelope.encode(),
+            ProgramProvingReport::new(elapsed),
+        ))
     }
 
-    fn verify(&self, proof_with_public_inputs: &[u8]) -> Result<(), zkVMError> {
-        let preprocessed_verifier = preprocess_verifier(&self.program);
-        let (public_inputs, proof) =
-            deserialize_public_input_with_proof(proof_with_public_inputs).unwrap();
+    fn program_id(&self) -> ProgramDigest {
+        self.program_id
+    }
 
-        let mut outputs = Input::new();
-        assert!(public_inputs.is_empty());
-        outputs.write(public_inputs);
+    fn input_encoding(&self) -> InputEncoding {
+        InputEncoding::Postcard
+    }
+}
 
-        // TODO: I don't think we should require the inputs when verifying
-        let inputs = Input::new();
+impl zkVMVerifier for EreJolt {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+        envelope.ensure_program(&self.program_id)?;
+        verify_proof(&self.verifier_preprocessing, &envelope.payload)
+    }
 
-        let valid = verify_generic(proof, inputs, outputs, preprocessed_verifier);
-        if valid {
-            Ok(())
-        } else {
-            Err(zkVMError::from(JoltError::ProofVerificationFailed))
-        }
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Postcard
     }
 
     fn name(&self) -> &'static str {
@@ -113,11 +312,82 @@ impl zkVM for EreJolt {
     }
 }
 
+/// Verifier-only handle for proofs of a single Jolt program, built from its
+/// verifier preprocessing alone.
+pub struct EreJoltVerifier {
+    verifier_preprocessing: JoltVerifierPreprocessing,
+}
+
+impl EreJoltVerifier {
+    /// Creates a verifier from the compressed verifier preprocessing, as
+    /// returned by [`EreJolt::verifier_preprocessing_bytes`].
+    pub fn from_preprocessing(verifier_preprocessing: &[u8]) -> Result<Self, JoltError> {
+        let verifier_preprocessing =
+            JoltVerifierPreprocessing::deserialize_compressed(verifier_preprocessing)
+                .map_err(KeysError::from)?;
+        Ok(Self {
+            verifier_preprocessing,
+        })
+    }
+}
+
+impl zkVMVerifier for EreJoltVerifier {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+        verify_proof(&self.verifier_preprocessing, &envelope.payload)
+    }
+
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Postcard
+    }
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+}
+
+/// Verifies the proof against the inputs and outputs it carries, returning the
+/// outputs as the public values.
+fn verify_proof(
+    verifier_preprocessing: &JoltVerifierPreprocessing,
+    proof_with_io: &[u8],
+) -> Result<PublicValues, zkVMError> {
+    let (inputs, outputs, proof) =
+        deserialize_io_with_proof(proof_with_io).map_err(JoltError::DecodeProof)?;
+
+    let valid = verify_generic(
+        proof,
+        inputs,
+        outputs.clone(),
+        verifier_preprocessing.clone(),
+    );
+    if valid {
+        Ok(outputs)
+    } else {
+        Err(zkVMError::from(JoltError::ProofVerificationFailed))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{EreJolt, JOLT_TARGET};
-    use std::path::PathBuf;
-    use zkvm_interface::{Compiler, Input, ProverResourceType, zkVM};
+    use crate::{
+        EreJolt, EreJoltVerifier, JOLT_TARGET, JoltConfig, JoltError, JoltProgram, KeysError, NAME,
+        PROGRAM_DIGEST_FILE, SDK_VERSION,
+        utils::{deserialize_io_with_proof, serialize_io_with_proof},
+    };
+    use jolt_core::host::Program;
+    use serde::de::DeserializeOwned;
+    use std::{path::PathBuf, time::Duration};
+    use zkvm_interface::{
+        Compiler, ExecutionLimits, Input, InputEncoding, ProofEnvelope, ProofKind,
+        ProverResourceType,
+        test_utils::{GuestInputReader, check_input_parity, check_prove_verify},
+        zkVM, zkVMError, zkVMVerifier,
+    };
 
     // TODO: for now, we just get one test file
     // TODO: but this should get the whole directory and compile each test
@@ -137,7 +407,28 @@ mod tests {
     fn test_compile_trait() {
         let test_guest_path = get_compile_test_guest_program_path();
         let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
-        assert!(program.elf.is_some(), "elf has not been compiled");
+        assert!(program.program.elf.is_some(), "elf has not been compiled");
+    }
+
+    #[test]
+    fn test_compile_missing_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            JOLT_TARGET::compile(dir.path()),
+            Err(JoltError::ReadManifest { .. })
+        ));
+    }
+
+    #[test]
+    fn test_try_new_unbuilt_program() {
+        let program = JoltProgram {
+            program: Program::new("unbuilt"),
+            config: JoltConfig::default(),
+        };
+        assert!(matches!(
+            EreJolt::try_new(program, ProverResourceType::Cpu),
+            Err(JoltError::Keys(KeysError::MissingElf))
+        ));
     }
 
     #[test]
@@ -145,20 +436,175 @@ mod tests {
         let test_guest_path = get_compile_test_guest_program_path();
         let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
         let mut inputs = Input::new();
-        inputs.write(1 as u32);
+        inputs.write(10u32);
 
         let zkvm = EreJolt::new(program, ProverResourceType::Cpu);
-        let _execution = zkvm.execute(&inputs).unwrap();
+        let (public_values, report) = zkvm.execute(&inputs).unwrap();
+        assert!(report.total_num_cycles > 0);
+        // The guest tracks the `fib_loop` region, so it has to be reported.
+        let fib_loop_cycles = *report
+            .region_cycles
+            .get("fib_loop")
+            .expect("no cycles reported for the `fib_loop` region");
+        assert!(0 < fib_loop_cycles && fib_loop_cycles < report.total_num_cycles);
+
+        // The guest returns the 10th Fibonacci number as a `u128`.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<u128>().unwrap(), 55);
     }
-    // #[test]
-    // fn test_prove_verify() {
-    //     let test_guest_path = get_compile_test_guest_program_path();
-    //     let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
 
-    //     // TODO: I don't think we should require the inputs when verifying
-    //     let inputs = Input::new();
+    #[test]
+    fn test_execute_empty_input() {
+        // Fails because the program expects an argument, but we supply none.
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
+        let zkvm = EreJolt::new(program, ProverResourceType::Cpu);
+
+        match zkvm.execute(&Input::new()) {
+            Err(zkVMError::GuestPanicked {
+                message, output, ..
+            }) => assert_eq!(message, output.panic_message()),
+            other => panic!("expected a guest panic, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_limits() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
+        let mut inputs = Input::new();
+        inputs.write(10u32);
+
+        let zkvm = EreJolt::new(program, ProverResourceType::Cpu);
+        let limits = ExecutionLimits::new().with_max_duration(Duration::from_nanos(1));
+        assert!(matches!(
+            zkvm.execute_with_limits(&inputs, &limits),
+            Err(zkVMError::Timeout(_))
+        ));
+
+        // The tracer cannot be stopped after a number of cycles.
+        let limits = ExecutionLimits::new().with_max_cycles(u64::MAX);
+        assert!(matches!(
+            zkvm.execute_with_limits(&inputs, &limits),
+            Err(zkVMError::Unsupported(_))
+        ));
+    }
+
+    /// The `#[jolt::provable]` function takes its arguments one after another
+    /// from the input buffer with `postcard`, raw bytes being a `Vec<u8>`.
+    struct JoltGuest {
+        input: Vec<u8>,
+        offset: usize,
+    }
+
+    impl GuestInputReader for JoltGuest {
+        fn read<T: DeserializeOwned>(&mut self) -> T {
+            let input = &self.input[self.offset..];
+            let (value, rest) = postcard::take_from_bytes(input).unwrap();
+            self.offset += input.len() - rest.len();
+            value
+        }
+
+        fn read_bytes(&mut self) -> Vec<u8> {
+            self.read()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.offset == self.input.len()
+        }
+    }
 
-    //     let (proof, _) = EreJolt::prove(&program, &inputs).unwrap();
-    //     EreJolt::verify(&program, &proof).unwrap();
-    // }
+    #[test]
+    fn test_input_parity() {
+        let config = JoltConfig::default();
+        check_input_parity(InputEncoding::Postcard, |input| JoltGuest {
+            input: crate::serialize_inputs(input, &config).unwrap(),
+            offset: 0,
+        });
+    }
+
+    #[test]
+    fn test_input_too_large() {
+        let config = JoltConfig {
+            max_input_size: 16,
+            ..Default::default()
+        };
+
+        let mut inputs = Input::new();
+        inputs.write_bytes(vec![0; 16]);
+        assert_eq!(crate::serialize_inputs(&inputs, &config).unwrap().len(), 16);
+
+        inputs.write(1u8);
+        assert!(matches!(
+            crate::serialize_inputs(&inputs, &config),
+            Err(JoltError::InputTooLarge {
+                size: 17,
+                max_input_size: 16
+            })
+        ));
+    }
+
+    #[test]
+    fn test_verifier_from_preprocessing() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
+
+        let zkvm = EreJolt::new(program, ProverResourceType::Cpu);
+        let verifier_preprocessing = zkvm.verifier_preprocessing_bytes().unwrap();
+        EreJoltVerifier::from_preprocessing(&verifier_preprocessing)
+            .expect("verifier preprocessing should round-trip");
+
+        assert!(matches!(
+            EreJoltVerifier::from_preprocessing(&verifier_preprocessing[..1]),
+            Err(JoltError::Keys(KeysError::Serialization(_)))
+        ));
+    }
+
+    #[test]
+    fn test_export_and_load_keys() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
+        let keys_dir = tempfile::tempdir().unwrap();
+
+        let zkvm = EreJolt::new(program.clone(), ProverResourceType::Cpu);
+        zkvm.export_keys(keys_dir.path()).unwrap();
+        let loaded = EreJolt::load_keys(program.clone(), ProverResourceType::Cpu, keys_dir.path())
+            .expect("keys should load for the program they were generated for");
+        assert_eq!(loaded.program_id(), zkvm.program_id());
+
+        // Pretend the keys were exported for another program.
+        std::fs::write(keys_dir.path().join(PROGRAM_DIGEST_FILE), [0; 32]).unwrap();
+        assert!(matches!(
+            EreJolt::load_keys(program, ProverResourceType::Cpu, keys_dir.path()),
+            Err(JoltError::Keys(KeysError::ProgramMismatch(_)))
+        ));
+    }
+
+    #[test]
+    fn test_prove_verify() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = JOLT_TARGET::compile(&test_guest_path).unwrap();
+        let mut inputs = Input::new();
+        inputs.write(10u32);
+
+        let program_config = program.config;
+        let zkvm = EreJolt::new(program, ProverResourceType::Cpu);
+        let (_, proof) = check_prove_verify(&zkvm, &inputs, ProofKind::Core);
+
+        // The proof is bound to the inputs it was generated for.
+        let mut envelope = ProofEnvelope::decode(&proof, NAME, SDK_VERSION).unwrap();
+        let (_, outputs, jolt_proof) = deserialize_io_with_proof(&envelope.payload).unwrap();
+        let mut other_inputs = Input::new();
+        other_inputs.write(11u32);
+        envelope.payload = serialize_io_with_proof(
+            &crate::serialize_inputs(&other_inputs, &program_config).unwrap(),
+            &outputs,
+            &jolt_proof,
+        )
+        .unwrap();
+        assert!(matches!(
+            zkvm.verify(&envelope.encode()),
+            Err(zkVMError::InvalidProof(_))
+        ));
+    }
 }
@@ -1,74 +1,114 @@
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use jolt::JoltHyperKZGProof;
 use std::io::Cursor;
-use std::{fs, path::Path};
+use std::{any::Any, fs, path::Path};
 use toml::Value;
 
-use crate::JoltError;
+use crate::{JoltConfig, JoltError};
 
 /// Reads the `[package] name` out of a Cargo.toml.
 ///
 /// * `manifest_path` – absolute or relative path to a Cargo.toml.
 /// * Returns → `String` with the package name (`fib`, `my_guest`, …).
 pub(crate) fn package_name_from_manifest(manifest_path: &Path) -> Result<String, JoltError> {
-    let manifest = fs::read_to_string(manifest_path).unwrap();
-    let value: Value = manifest.parse::<Value>().unwrap();
-
-    value
+    read_manifest(manifest_path)?
         .get("package")
         .and_then(|pkg| pkg.get("name"))
         .and_then(Value::as_str)
         .map(|s| s.to_owned())
-        .ok_or_else(|| panic!("no [package] name found in {}", manifest_path.display()))
+        .ok_or_else(|| JoltError::MissingPackageName(manifest_path.to_path_buf()))
+}
+
+/// Reads the [`JoltConfig`] out of the `[package.metadata.jolt]` table of a
+/// Cargo.toml, falling back to the defaults if there is none.
+pub(crate) fn jolt_config_from_manifest(manifest_path: &Path) -> Result<JoltConfig, JoltError> {
+    match read_manifest(manifest_path)?
+        .get("package")
+        .and_then(|pkg| pkg.get("metadata"))
+        .and_then(|metadata| metadata.get("jolt"))
+    {
+        Some(config) => config
+            .clone()
+            .try_into()
+            .map_err(|source| JoltError::InvalidConfig {
+                path: manifest_path.to_path_buf(),
+                source,
+            }),
+        None => Ok(JoltConfig::default()),
+    }
+}
+
+fn read_manifest(manifest_path: &Path) -> Result<Value, JoltError> {
+    let manifest = fs::read_to_string(manifest_path).map_err(|source| JoltError::ReadManifest {
+        path: manifest_path.to_path_buf(),
+        source,
+    })?;
+    manifest
+        .parse::<Value>()
+        .map_err(|source| JoltError::ParseManifest {
+            path: manifest_path.to_path_buf(),
+            source,
+        })
+}
+
+/// Extracts the message of a caught panic, which is a `&str` or a `String`
+/// when raised by `panic!`.
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
 }
 
-/// Serializes the public input (as raw bytes) and proof into a single byte vector
-pub fn serialize_public_input_with_proof(
-    public_input: &[u8],
+/// Serializes the inputs and outputs of the program (as raw bytes) and the
+/// proof into a single byte vector.
+///
+/// Jolt proofs are checked against the inputs as well as the outputs, so both
+/// travel with the proof.
+pub fn serialize_io_with_proof(
+    inputs: &[u8],
+    outputs: &[u8],
     proof: &JoltHyperKZGProof,
 ) -> Result<Vec<u8>, SerializationError> {
     let mut buffer = Vec::new();
 
-    // First, serialize the length of the public input as u64
-    let public_input_size = public_input.len() as u64;
-    public_input_size.serialize_compressed(&mut buffer)?;
-
-    // Append the public input directly (it's already bytes)
-    buffer.extend_from_slice(public_input);
-
-    // Now serialize the proof
-    let mut proof_bytes = Vec::new();
-    proof.serialize_compressed(&mut proof_bytes)?;
+    // Each of the inputs and outputs is prefixed by its length as u64
+    for bytes in [inputs, outputs] {
+        (bytes.len() as u64).serialize_compressed(&mut buffer)?;
+        buffer.extend_from_slice(bytes);
+    }
 
     // Append the serialized proof to the buffer
-    buffer.extend_from_slice(&proof_bytes);
+    proof.serialize_compressed(&mut buffer)?;
 
     Ok(buffer)
 }
 
-/// Deserializes a byte vector into a public input (Vec<u8>) and proof
-pub fn deserialize_public_input_with_proof(
+/// Deserializes a byte vector into the inputs, outputs and proof written by
+/// [`serialize_io_with_proof`].
+pub fn deserialize_io_with_proof(
     bytes: &[u8],
-) -> Result<(Vec<u8>, JoltHyperKZGProof), SerializationError> {
+) -> Result<(Vec<u8>, Vec<u8>, JoltHyperKZGProof), SerializationError> {
     let mut cursor = Cursor::new(bytes);
+    let inputs = read_length_prefixed(&mut cursor)?;
+    let outputs = read_length_prefixed(&mut cursor)?;
 
-    // Read the size of the public input
-    let public_input_size: u64 = CanonicalDeserialize::deserialize_compressed(&mut cursor)?;
-
-    // Get the current position after reading the size
-    let current_position = cursor.position() as usize;
-    let public_input_end = current_position + public_input_size as usize;
+    // The rest is the proof
+    let proof = JoltHyperKZGProof::deserialize_compressed(&mut cursor)?;
 
-    if public_input_end > bytes.len() {
-        return Err(SerializationError::InvalidData);
-    }
+    Ok((inputs, outputs, proof))
+}
 
-    // Extract the public input bytes directly
-    let public_input = bytes[current_position..public_input_end].to_vec();
+fn read_length_prefixed(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, SerializationError> {
+    let len: u64 = CanonicalDeserialize::deserialize_compressed(&mut *cursor)?;
 
-    // The rest is the proof
-    let proof_bytes = &bytes[public_input_end..];
-    let proof = JoltHyperKZGProof::deserialize_compressed(&mut Cursor::new(proof_bytes))?;
+    let start = cursor.position() as usize;
+    let end = start
+        .checked_add(len as usize)
+        .filter(|end| *end <= cursor.get_ref().len())
+        .ok_or(SerializationError::InvalidData)?;
+    cursor.set_position(end as u64);
 
-    Ok((public_input, proof))
+    Ok(cursor.get_ref()[start..end].to_vec())
 }
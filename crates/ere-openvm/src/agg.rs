@@ -0,0 +1,125 @@
+use std::borrow::Borrow;
+
+use openvm_circuit::{
+    arch::{
+        CONNECTOR_AIR_ID, PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
+        hasher::{Hasher, poseidon2::vm_poseidon2_hasher},
+    },
+    system::program::trace::compute_exe_commit,
+};
+use openvm_continuations::verifier::{
+    common::types::VmVerifierPvs, internal::types::InternalVmVerifierPvs,
+};
+use openvm_sdk::{F, commit::AppExecutionCommit, keygen::AggStarkProvingKey, types::VmStarkProof};
+use openvm_stark_sdk::{
+    config::{
+        FriParameters,
+        baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+    },
+    engine::StarkFriEngine,
+    openvm_stark_backend::keygen::types::MultiStarkVerifyingKey,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::VerifyError;
+
+type SC = BabyBearPoseidon2Config;
+
+/// Number of field elements of a commitment.
+const DIGEST_SIZE: usize = 8;
+
+/// Verifying key of one of the aggregation VMs, with the FRI parameters its
+/// proofs are generated with.
+#[derive(Serialize, Deserialize)]
+struct AggVmVerifyingKey {
+    fri_params: FriParameters,
+    vk: MultiStarkVerifyingKey<SC>,
+}
+
+/// The part of the `AggStarkProvingKey` needed to verify aggregated STARK
+/// proofs: the verifying keys of the leaf and internal aggregation VMs, and the
+/// commitment to the program of the internal one.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AggStarkVerifyingKey {
+    leaf: AggVmVerifyingKey,
+    internal: AggVmVerifyingKey,
+    internal_program_commit: [F; DIGEST_SIZE],
+}
+
+impl AggStarkVerifyingKey {
+    pub(crate) fn new(agg_stark_pk: &AggStarkProvingKey) -> Self {
+        Self {
+            leaf: AggVmVerifyingKey {
+                fri_params: agg_stark_pk.leaf_vm_pk.fri_params,
+                vk: agg_stark_pk.leaf_vm_pk.vm_pk.get_vk(),
+            },
+            internal: AggVmVerifyingKey {
+                fri_params: agg_stark_pk.internal_vm_pk.fri_params,
+                vk: agg_stark_pk.internal_vm_pk.vm_pk.get_vk(),
+            },
+            internal_program_commit: agg_stark_pk
+                .internal_committed_exe
+                .get_program_commit()
+                .into(),
+        }
+    }
+
+    /// Verifies an aggregated STARK proof like `Sdk::verify_e2e_stark_proof`,
+    /// which takes the whole aggregation proving key, and returns the
+    /// commitments to the app exe and VM whose execution it proves.
+    pub(crate) fn verify(
+        &self,
+        proof: &VmStarkProof<SC>,
+    ) -> Result<AppExecutionCommit, VerifyError> {
+        let air_ids = proof.inner.per_air.iter().map(|air| air.air_id);
+        if !air_ids
+            .take(3)
+            .eq([PROGRAM_AIR_ID, CONNECTOR_AIR_ID, PUBLIC_VALUES_AIR_ID])
+        {
+            return Err(VerifyError::AggregatedProof(
+                "missing program, connector or public values AIR",
+            ));
+        }
+        let public_values = &proof.inner.per_air[2].public_values;
+
+        // The proof is of the last aggregation layer, either a leaf or an
+        // internal verifier, told apart by the program it runs.
+        let program_commit: [F; DIGEST_SIZE] =
+            proof.inner.commitments.main_trace[PROGRAM_CACHED_TRACE_INDEX].into();
+        let (key, vm_commit) = if program_commit == self.internal_program_commit {
+            let internal_pvs: &InternalVmVerifierPvs<F> = public_values.as_slice().borrow();
+            if internal_pvs.extra_pvs.internal_program_commit != self.internal_program_commit {
+                return Err(VerifyError::AggregatedProof(
+                    "internal program commitment mismatch",
+                ));
+            }
+            (&self.internal, internal_pvs.extra_pvs.leaf_verifier_commit)
+        } else {
+            (&self.leaf, program_commit)
+        };
+        BabyBearPoseidon2Engine::new(key.fri_params)
+            .verify(&key.vk, &proof.inner)
+            .map_err(|e| VerifyError::Client(e.into()))?;
+
+        let pvs: &VmVerifierPvs<F> = public_values[..VmVerifierPvs::<u8>::width()].borrow();
+        if pvs.connector.exit_code() != Some(0) {
+            return Err(VerifyError::AggregatedProof(
+                "guest did not exit successfully",
+            ));
+        }
+        let hasher = vm_poseidon2_hasher();
+        if hasher.merkle_root(&proof.user_public_values) != pvs.public_values_commit {
+            return Err(VerifyError::AggregatedProof(
+                "public values commitment mismatch",
+            ));
+        }
+
+        let exe_commit = compute_exe_commit(
+            &hasher,
+            &pvs.app_commit,
+            &pvs.memory.initial_root,
+            pvs.connector.initial_pc,
+        );
+        Ok(AppExecutionCommit::from_field_commit(exe_commit, vm_commit))
+    }
+}
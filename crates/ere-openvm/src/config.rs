@@ -0,0 +1,117 @@
+use std::{fs, path::Path};
+
+use openvm_circuit::arch::VmConfig;
+use openvm_sdk::config::{AggStarkConfig, AppConfig, SdkVmConfig};
+use openvm_stark_sdk::config::FriParameters;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CompileError;
+
+/// File name of the config read by [`OpenVMConfig::from_guest_dir`].
+const OPENVM_TOML: &str = "openvm.toml";
+
+/// VM extensions and FRI parameters of an OpenVM guest, which drive
+/// transpilation, keygen, execution and verification.
+///
+/// Uses the same format as the `openvm.toml` read by `cargo openvm`, e.g.
+///
+/// ```toml
+/// [app_fri_params.fri_params]
+/// log_blowup = 1
+/// log_final_poly_len = 0
+/// num_queries = 100
+/// proof_of_work_bits = 16
+///
+/// [app_vm_config.rv32i]
+/// [app_vm_config.rv32m]
+/// [app_vm_config.io]
+/// [app_vm_config.keccak]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OpenVMConfig(pub AppConfig<SdkVmConfig>);
+
+impl Default for OpenVMConfig {
+    /// RV32IM with IO and no other extensions, with fast FRI parameters.
+    fn default() -> Self {
+        let vm_config = SdkVmConfig::builder()
+            .system(Default::default())
+            .rv32i(Default::default())
+            .rv32m(Default::default())
+            .io(Default::default())
+            .build();
+        Self(AppConfig::new(FriParameters::standard_fast(), vm_config))
+    }
+}
+
+impl OpenVMConfig {
+    /// Parses a config in the `openvm.toml` format.
+    pub fn from_toml(config: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(config)
+    }
+
+    /// Reads the `openvm.toml` next to the `Cargo.toml` of the guest, or
+    /// returns the default config if there is none.
+    pub fn from_guest_dir(path_to_program: &Path) -> Result<Self, CompileError> {
+        let path = path_to_program.join(OPENVM_TOML);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let config = fs::read_to_string(&path).map_err(|source| CompileError::ReadConfig {
+            path: path.clone(),
+            source,
+        })?;
+        Self::from_toml(&config).map_err(|source| CompileError::InvalidConfig { path, source })
+    }
+
+    pub fn vm_config(&self) -> &SdkVmConfig {
+        &self.0.app_vm_config
+    }
+
+    pub fn fri_params(&self) -> &FriParameters {
+        &self.0.app_fri_params.fri_params
+    }
+
+    /// Config of the aggregation of app proofs, whose leaf verifier has to
+    /// agree with the app config on public values and FRI parameters.
+    pub fn agg_stark_config(&self) -> AggStarkConfig {
+        AggStarkConfig {
+            max_num_user_public_values: self.vm_config().system().num_public_values,
+            leaf_fri_params: self.0.leaf_fri_params.fri_params,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OpenVMConfig;
+
+    #[test]
+    fn test_from_toml() {
+        let config = OpenVMConfig::from_toml(
+            r#"
+            [app_fri_params.fri_params]
+            log_blowup = 2
+            log_final_poly_len = 0
+            num_queries = 50
+            proof_of_work_bits = 16
+
+            [app_vm_config.rv32i]
+            [app_vm_config.rv32m]
+            [app_vm_config.io]
+            [app_vm_config.keccak]
+            [app_vm_config.sha256]
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.vm_config().keccak.is_some());
+        assert!(config.vm_config().sha256.is_some());
+        assert!(config.vm_config().bigint.is_none());
+        assert_eq!(config.fri_params().log_blowup, 2);
+
+        assert!(OpenVMConfig::from_toml("[app_vm_config.rv32i").is_err());
+    }
+}
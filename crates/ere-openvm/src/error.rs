@@ -1,9 +1,46 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
 use thiserror::Error;
-use zkvm_interface::zkVMError;
+use zkvm_interface::{GuestOutput, ProgramDigest, ProofKind, zkVMError};
 
 impl From<OpenVMError> for zkVMError {
     fn from(value: OpenVMError) -> Self {
-        zkVMError::Other(Box::new(value))
+        if let OpenVMError::Execute(ExecuteError::GuestPanicked { exit_code, output }) = &value {
+            return zkVMError::GuestPanicked {
+                exit_code: Some(*exit_code as i32),
+                message: output.panic_message(),
+                output: output.clone(),
+                source: Box::new(value),
+            };
+        }
+        match value {
+            err @ (OpenVMError::Compile(_)
+            | OpenVMError::Keys(KeysError::AppKeygen(_) | KeysError::CommitExe(_))) => {
+                zkVMError::Compile(Box::new(err))
+            }
+            err @ OpenVMError::Execute(ExecuteError::CycleLimitExceeded { .. }) => {
+                zkVMError::CycleLimitExceeded(Box::new(err))
+            }
+            OpenVMError::Execute(ExecuteError::Timeout(max_duration)) => {
+                zkVMError::Timeout(max_duration)
+            }
+            err @ OpenVMError::Verify(VerifyError::Decode(_)) => {
+                zkVMError::ProofDecode(Box::new(err))
+            }
+            err @ OpenVMError::Verify(
+                VerifyError::Client(_)
+                | VerifyError::ProgramMismatch { .. }
+                | VerifyError::AppCommitMismatch
+                | VerifyError::AggregatedProof(_),
+            ) => zkVMError::InvalidProof(Box::new(err)),
+            err @ OpenVMError::Verify(VerifyError::MissingVerifyingKey(_)) => {
+                zkVMError::ResourceUnavailable(Box::new(err))
+            }
+            err => zkVMError::Other(Box::new(err)),
+        }
     }
 }
 
@@ -12,18 +49,125 @@ pub enum OpenVMError {
     #[error(transparent)]
     Compile(#[from] CompileError),
 
+    #[error(transparent)]
+    Execute(#[from] ExecuteError),
+
+    #[error(transparent)]
+    Prove(#[from] ProveError),
+
     #[error(transparent)]
     Verify(#[from] VerifyError),
+
+    #[error(transparent)]
+    Keys(#[from] KeysError),
 }
 
 #[derive(Debug, Error)]
 pub enum CompileError {
     #[error("OpenVM execution failed: {0}")]
     Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("Failed to read OpenVM config {path}: {source}")]
+    ReadConfig {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid OpenVM config {path}: {source}")]
+    InvalidConfig {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum ExecuteError {
+    #[error("OpenVM execution failed: {0}")]
+    Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("OpenVM guest exited with non-zero exit code {exit_code}")]
+    GuestPanicked { exit_code: u32, output: GuestOutput },
+
+    #[error("Failed to capture the output of the guest: {0}")]
+    CaptureOutput(#[source] std::io::Error),
+
+    #[error("OpenVM execution ended without the final memory of the last segment")]
+    MissingFinalMemory,
+
+    #[error("OpenVM execution exceeded the limit of {max_cycles} cycles")]
+    CycleLimitExceeded { max_cycles: u64 },
+
+    #[error("OpenVM execution timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+#[derive(Debug, Error)]
+pub enum ProveError {
+    #[error("Generating OpenVM aggregation keys failed: {0}")]
+    AggKeygen(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("Encoding proof failed: {0}")]
+    Encode(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 
 #[derive(Debug, Error)]
 pub enum VerifyError {
     #[error("OpenVM verification failed: {0}")]
     Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("Decoding proof failed: {0}")]
+    Decode(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("Deserialising app verifying key failed: {0}")]
+    AppVerifyingKey(#[source] bincode::Error),
+
+    #[error("Proof is of program {actual}, expected {expected}")]
+    ProgramMismatch {
+        expected: ProgramDigest,
+        actual: ProgramDigest,
+    },
+
+    #[error("Proof commits to a different app exe or VM config than the program's")]
+    AppCommitMismatch,
+
+    #[error("Invalid aggregated proof: {0}")]
+    AggregatedProof(&'static str),
+
+    #[error(
+        "No verifying key for {0} proofs, they are generated by proving or loaded with `load_keys`"
+    )]
+    MissingVerifyingKey(ProofKind),
+}
+
+#[derive(Debug, Error)]
+pub enum KeysError {
+    #[error("Failed to access key file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Serialising keys with `bincode` failed: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    #[error("Keys in {0} were generated for a different program")]
+    ProgramMismatch(PathBuf),
+
+    #[error("Generating OpenVM app proving key failed: {0}")]
+    AppKeygen(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("Committing OpenVM app exe failed: {0}")]
+    CommitExe(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl KeysError {
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
 }
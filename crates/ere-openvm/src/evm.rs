@@ -0,0 +1,132 @@
+use std::{path::Path, sync::Arc};
+
+use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
+use openvm_sdk::{
+    DefaultStaticVerifierPvHandler, Sdk, StdIn,
+    config::{AggConfig, AggregationTreeConfig, SdkVmConfig},
+    keygen::AggProvingKey,
+    prover::EvmHalo2Prover,
+    types::{EvmHalo2Verifier, EvmProof},
+};
+use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Engine;
+use zkvm_interface::{ProofKind, PublicValues};
+
+use crate::{
+    EreOpenVM, KeyCell,
+    error::{KeysError, OpenVMError, ProveError, VerifyError},
+};
+
+/// File name of the `bincode`-encoded halo2 aggregation proving key.
+const EVM_PROVING_KEY_FILE: &str = "evm_agg_pk.bin";
+/// File name of the `bincode`-encoded halo2 verifier.
+const EVM_VERIFIER_FILE: &str = "evm_verifier.bin";
+
+/// Halo2 aggregation proving key, and the Solidity verifier generated from it,
+/// which is all verifying EVM proofs needs.
+#[derive(Default)]
+pub(crate) struct EvmKeys {
+    agg_pk: KeyCell<AggProvingKey>,
+    verifier: KeyCell<EvmHalo2Verifier>,
+    /// Prover built from `agg_pk` on first use, so that the keys it holds are
+    /// not copied for every proof.
+    prover: KeyCell<EvmHalo2Prover<SdkVmConfig, BabyBearPoseidon2Engine>>,
+}
+
+impl EvmKeys {
+    /// Writes the keys that have been generated or loaded to `dir`.
+    pub(crate) fn export(&self, dir: &Path) -> Result<(), KeysError> {
+        self.agg_pk.export(&dir.join(EVM_PROVING_KEY_FILE))?;
+        self.verifier.export(&dir.join(EVM_VERIFIER_FILE))
+    }
+
+    /// Reads the keys that are present in `dir`.
+    pub(crate) fn load(dir: &Path) -> Result<Self, KeysError> {
+        Ok(Self {
+            agg_pk: KeyCell::load(&dir.join(EVM_PROVING_KEY_FILE))?,
+            verifier: KeyCell::load(&dir.join(EVM_VERIFIER_FILE))?,
+            prover: KeyCell::default(),
+        })
+    }
+}
+
+/// Reads the KZG parameters from `~/.openvm/params`, where `cargo openvm setup`
+/// downloads them to.
+fn params_reader() -> CacheHalo2ParamsReader {
+    CacheHalo2ParamsReader::new_with_default_params_dir()
+}
+
+impl EreOpenVM {
+    /// Returns the halo2 aggregation proving key, generating it and the
+    /// verifier on first use.
+    fn evm_agg_pk(&self) -> Result<Arc<AggProvingKey>, OpenVMError> {
+        Ok(self.evm_keys.agg_pk.get_or_try_init(|| {
+            let sdk = Sdk::new();
+            let reader = params_reader();
+            let agg_config = AggConfig {
+                agg_stark_config: self.config.agg_stark_config(),
+                halo2_config: Default::default(),
+            };
+            let agg_pk = sdk
+                .agg_keygen(agg_config, &reader, &DefaultStaticVerifierPvHandler)
+                .map_err(|e| ProveError::AggKeygen(e.into()))?;
+            let verifier = sdk
+                .generate_halo2_verifier_solidity(&reader, &agg_pk)
+                .map_err(|e| ProveError::AggKeygen(e.into()))?;
+            self.evm_keys.verifier.set(verifier);
+            Ok::<_, ProveError>(agg_pk)
+        })?)
+    }
+
+    /// Returns the prover of EVM proofs, building it on first use.
+    fn evm_prover(
+        &self,
+    ) -> Result<Arc<EvmHalo2Prover<SdkVmConfig, BabyBearPoseidon2Engine>>, OpenVMError> {
+        self.evm_keys.prover.get_or_try_init(|| {
+            // The prover takes the aggregation key by value, so it is copied
+            // once here rather than for every proof.
+            let agg_pk = self.evm_agg_pk()?;
+            Ok(EvmHalo2Prover::new(
+                &params_reader(),
+                Arc::clone(&self.app_pk),
+                Arc::clone(&self.app_committed_exe),
+                (*agg_pk).clone(),
+                AggregationTreeConfig::default(),
+            ))
+        })
+    }
+
+    /// Proves the execution, aggregates the app proof and wraps it into a halo2
+    /// proof that can be verified on-chain.
+    pub(crate) fn prove_evm(&self, stdin: StdIn) -> Result<(PublicValues, Vec<u8>), OpenVMError> {
+        let proof = self.evm_prover()?.generate_proof_for_evm(stdin);
+
+        let proof_bytes = bincode::serialize(&proof).map_err(|e| ProveError::Encode(e.into()))?;
+        Ok((proof.user_public_values, proof_bytes))
+    }
+
+    pub(crate) fn verify_evm_proof(&self, proof: &[u8]) -> Result<PublicValues, OpenVMError> {
+        let verifier = self
+            .evm_keys
+            .verifier
+            .get()
+            .ok_or(VerifyError::MissingVerifyingKey(ProofKind::Halo2))?;
+        let proof: EvmProof =
+            bincode::deserialize(proof).map_err(|e| VerifyError::Decode(e.into()))?;
+        let public_values = proof.user_public_values.clone();
+
+        // The halo2 verifier takes the app commitments from the proof itself,
+        // so they are checked against this program's.
+        let commit = self.app_execution_commit();
+        if proof.app_commit.app_exe_commit != commit.app_exe_commit
+            || proof.app_commit.app_vm_commit != commit.app_vm_commit
+        {
+            return Err(VerifyError::AppCommitMismatch.into());
+        }
+
+        Sdk::new()
+            .verify_evm_halo2_proof(&verifier, proof)
+            .map_err(|e| VerifyError::Client(e.into()))?;
+
+        Ok(public_values)
+    }
+}
@@ -1,26 +1,61 @@
-use std::time::Instant;
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Instant,
+};
 
 use openvm_build::GuestOptions;
-use openvm_circuit::arch::ContinuationVmProof;
+use openvm_circuit::{
+    arch::{ContinuationVmProof, ExecutionError, VmConfig, VmExecutor, instructions::exe::VmExe},
+    system::memory::tree::public_values::extract_public_values,
+};
 use openvm_sdk::{
-    Sdk, StdIn,
+    F, NonRootCommittedExe, Sdk, StdIn,
     codec::{Decode, Encode},
-    config::{AppConfig, SdkVmConfig},
-    prover::AppProver,
+    commit::AppExecutionCommit,
+    config::{AggregationTreeConfig, SdkVmConfig},
+    keygen::{AggStarkProvingKey, AppProvingKey, AppVerifyingKey},
+    prover::{AppProver, StarkProver},
+    types::VmStarkProof,
 };
-use openvm_stark_sdk::config::{
-    FriParameters, baby_bear_poseidon2::BabyBearPoseidon2Config,
-    baby_bear_poseidon2::BabyBearPoseidon2Engine,
+use openvm_stark_sdk::{
+    config::{
+        baby_bear_poseidon2::BabyBearPoseidon2Config, baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    },
+    openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32},
 };
 use openvm_transpiler::elf::Elf;
 use zkvm_interface::{
-    Compiler, Input, InputItem, ProgramExecutionReport, ProgramProvingReport, ProverResourceType,
-    zkVM, zkVMError,
+    Compiler, ExecutionLimits, GuestOutput, Input, InputEncoding, InputError, InputItem,
+    ProgramDigest, ProgramExecutionReport, ProgramProvingReport, ProofEnvelope, ProofKind,
+    ProverResourceType, PublicValues, PublicValuesEncoding, capture_output, open_input_file, zkVM,
+    zkVMError, zkVMVerifier,
 };
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
+mod agg;
+mod config;
 mod error;
-use error::{CompileError, OpenVMError, VerifyError};
+#[cfg(feature = "evm")]
+mod evm;
+use agg::AggStarkVerifyingKey;
+use error::{CompileError, ExecuteError, KeysError, OpenVMError, ProveError, VerifyError};
+
+pub use config::OpenVMConfig;
+
+/// File name of the program id (the exe commitment) written by
+/// [`EreOpenVM::export_keys`].
+const PROGRAM_DIGEST_FILE: &str = "program_digest";
+/// File name of the `bincode`-encoded app proving key.
+const APP_PROVING_KEY_FILE: &str = "app_pk.bin";
+/// File name of the `bincode`-encoded committed app exe.
+const APP_COMMITTED_EXE_FILE: &str = "app_committed_exe.bin";
+/// File name of the `bincode`-encoded aggregation proving key.
+const AGG_STARK_PROVING_KEY_FILE: &str = "agg_stark_pk.bin";
+/// File name of the `bincode`-encoded verifying keys of the aggregation.
+const AGG_STARK_VERIFYING_KEY_FILE: &str = "agg_stark_vk.bin";
 
 #[allow(non_camel_case_types)]
 pub struct OPENVM_TARGET;
@@ -28,10 +63,11 @@ pub struct OPENVM_TARGET;
 impl Compiler for OPENVM_TARGET {
     type Error = OpenVMError;
 
-    type Program = Elf;
+    type Program = OpenVMProgram;
 
     fn compile(path_to_program: &std::path::Path) -> Result<Self::Program, Self::Error> {
         let sdk = Sdk::new();
+        let config = OpenVMConfig::from_guest_dir(path_to_program)?;
 
         // Build the guest crate
         let elf: Elf = sdk
@@ -43,126 +79,615 @@ impl Compiler for OPENVM_TARGET {
             .map_err(|e| CompileError::Client(e.into()))?;
         // TODO: note that this does not transpile (check to see how expensive that is)
 
-        Ok(elf)
+        Ok(OpenVMProgram { elf, config })
     }
 }
 
+/// A compiled OpenVM guest, together with the config read from its
+/// `openvm.toml`.
+#[derive(Clone)]
+pub struct OpenVMProgram {
+    pub elf: Elf,
+    pub config: OpenVMConfig,
+}
+
 pub struct EreOpenVM {
-    program: <OPENVM_TARGET as Compiler>::Program,
+    config: OpenVMConfig,
+    app_exe: VmExe<F>,
+    app_pk: Arc<AppProvingKey<SdkVmConfig>>,
+    app_committed_exe: Arc<NonRootCommittedExe>,
+    app_vk: AppVerifyingKey,
+    /// Commitment to the exe, which app proofs of the program carry.
+    program_id: ProgramDigest,
+    /// Proving key of the aggregation into a single STARK proof, generated on
+    /// first use since only compressed and EVM proofs need it.
+    agg_stark_pk: KeyCell<AggStarkProvingKey>,
+    /// Verifying keys of the aggregation, set along with `agg_stark_pk` or
+    /// loaded on their own, which is all verifying compressed proofs needs.
+    agg_stark_vk: KeyCell<AggStarkVerifyingKey>,
+    /// Prover of compressed proofs, built on first use so that the keys it
+    /// holds are not copied for every proof.
+    stark_prover: KeyCell<StarkProver<SdkVmConfig, BabyBearPoseidon2Engine>>,
+    /// Halo2 proving key and verifier of EVM proofs, generated on first use.
+    #[cfg(feature = "evm")]
+    evm_keys: evm::EvmKeys,
 }
 
 impl EreOpenVM {
+    /// Transpiles the program and generates its application proving and
+    /// verifying keys once, so they are reused by every later call.
+    /// # Panics
+    ///
+    /// Panics if transpilation or keygen fails, see [`EreOpenVM::try_new`].
     pub fn new(
         program: <OPENVM_TARGET as Compiler>::Program,
-        _resource_type: ProverResourceType,
+        resource_type: ProverResourceType,
     ) -> Self {
-        Self { program }
+        Self::try_new(program, resource_type).expect("Failed to set up OpenVM program")
     }
-}
-impl zkVM for EreOpenVM {
-    fn execute(&self, inputs: &Input) -> Result<zkvm_interface::ProgramExecutionReport, zkVMError> {
+
+    /// Like [`EreOpenVM::new`], but returns an error instead of panicking if the
+    /// program cannot be transpiled with its config, or keygen fails.
+    pub fn try_new(
+        program: <OPENVM_TARGET as Compiler>::Program,
+        _resource_type: ProverResourceType,
+    ) -> Result<Self, OpenVMError> {
         let sdk = Sdk::new();
-        let vm_cfg = SdkVmConfig::builder()
-            .system(Default::default())
-            .rv32i(Default::default())
-            .rv32m(Default::default())
-            .io(Default::default())
-            .build();
-
-        let exe = sdk
-            .transpile(self.program.clone(), vm_cfg.transpiler())
-            .map_err(|e| CompileError::Client(e.into()))
-            .map_err(OpenVMError::from)?;
+        let OpenVMProgram { elf, config } = program;
 
-        let mut stdin = StdIn::default();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => stdin.write(serialize),
-                InputItem::Bytes(items) => stdin.write_bytes(items),
-            }
-        }
+        let app_exe = sdk
+            .transpile(elf, config.vm_config().transpiler())
+            .map_err(|e| CompileError::Client(e.into()))?;
 
-        let start = Instant::now();
-        let _outputs = sdk
-            .execute(exe.clone(), vm_cfg.clone(), stdin)
-            .map_err(|e| CompileError::Client(e.into()))
-            .map_err(OpenVMError::from)?;
+        let app_pk = sdk
+            .app_keygen(config.0.clone())
+            .map_err(|e| KeysError::AppKeygen(e.into()))?;
+        let app_committed_exe = sdk
+            .commit_app_exe(app_pk.app_fri_params(), app_exe.clone())
+            .map_err(|e| KeysError::CommitExe(e.into()))?;
+        let app_vk = app_pk.get_app_vk();
+        let program_id = exe_commit_digest(&config, &app_committed_exe);
 
-        Ok(ProgramExecutionReport {
-            execution_duration: start.elapsed(),
-            ..Default::default()
+        Ok(Self {
+            config,
+            app_exe,
+            app_pk: Arc::new(app_pk),
+            app_committed_exe,
+            app_vk,
+            program_id,
+            agg_stark_pk: KeyCell::default(),
+            agg_stark_vk: KeyCell::default(),
+            stark_prover: KeyCell::default(),
+            #[cfg(feature = "evm")]
+            evm_keys: evm::EvmKeys::default(),
         })
     }
 
-    fn prove(
-        &self,
-        inputs: &Input,
-    ) -> Result<(Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
-        // TODO: We need a stateful version in order to not spend a lot of time
-        // TODO doing things like computing the pk and vk.
+    /// Returns the `bincode`-encoded app verifying key, from which an
+    /// [`EreOpenVMVerifier`] can be created.
+    pub fn app_vk_bytes(&self) -> Result<Vec<u8>, OpenVMError> {
+        Ok(bincode::serialize(&self.app_vk).map_err(KeysError::from)?)
+    }
 
+    /// Writes the app proving key and committed exe to `dir`, alongside the
+    /// [`zkVM::program_id`] they were generated for, so that
+    /// [`EreOpenVM::load_keys`] can skip keygen next time. The aggregation and
+    /// halo2 keys are written too if they have been generated or loaded.
+    pub fn export_keys(&self, dir: &Path) -> Result<(), OpenVMError> {
+        fs::create_dir_all(dir).map_err(|source| KeysError::io(dir, source))?;
+        write_key_file(&dir.join(PROGRAM_DIGEST_FILE), self.program_id.as_bytes())?;
+        let app_pk = bincode::serialize(&*self.app_pk).map_err(KeysError::from)?;
+        write_key_file(&dir.join(APP_PROVING_KEY_FILE), &app_pk)?;
+        let app_committed_exe =
+            bincode::serialize(&*self.app_committed_exe).map_err(KeysError::from)?;
+        write_key_file(&dir.join(APP_COMMITTED_EXE_FILE), &app_committed_exe)?;
+        self.agg_stark_pk
+            .export(&dir.join(AGG_STARK_PROVING_KEY_FILE))?;
+        self.agg_stark_vk
+            .export(&dir.join(AGG_STARK_VERIFYING_KEY_FILE))?;
+        #[cfg(feature = "evm")]
+        self.evm_keys.export(dir)?;
+        Ok(())
+    }
+
+    /// Creates an instance from keys previously written by
+    /// [`EreOpenVM::export_keys`] instead of running keygen. The program is
+    /// still transpiled and committed, which is cheap compared to keygen.
+    ///
+    /// Keys that were generated for a different program are rejected: the
+    /// committed exe has to commit to the transpiled program and match the
+    /// exported program id, and the app proving key has to be of the program's
+    /// VM config and FRI parameters.
+    pub fn load_keys(
+        program: <OPENVM_TARGET as Compiler>::Program,
+        _resource_type: ProverResourceType,
+        dir: &Path,
+    ) -> Result<Self, OpenVMError> {
         let sdk = Sdk::new();
-        let vm_cfg = SdkVmConfig::builder()
-            .system(Default::default())
-            .rv32i(Default::default())
-            .rv32m(Default::default())
-            .io(Default::default())
-            .build();
+        let OpenVMProgram { elf, config } = program;
 
         let app_exe = sdk
-            .transpile(self.program.clone(), vm_cfg.transpiler())
-            .map_err(|e| CompileError::Client(e.into()))
-            .map_err(OpenVMError::from)?;
+            .transpile(elf, config.vm_config().transpiler())
+            .map_err(|e| CompileError::Client(e.into()))?;
 
-        let mut stdin = StdIn::default();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => stdin.write(serialize),
-                InputItem::Bytes(items) => stdin.write_bytes(items),
-            }
+        let digest = read_key_file(&dir.join(PROGRAM_DIGEST_FILE))?;
+        let app_pk: AppProvingKey<SdkVmConfig> =
+            bincode::deserialize(&read_key_file(&dir.join(APP_PROVING_KEY_FILE))?)
+                .map_err(KeysError::from)?;
+        let app_committed_exe: NonRootCommittedExe =
+            bincode::deserialize(&read_key_file(&dir.join(APP_COMMITTED_EXE_FILE))?)
+                .map_err(KeysError::from)?;
+
+        let expected_committed_exe = sdk
+            .commit_app_exe(*config.fri_params(), app_exe.clone())
+            .map_err(|e| KeysError::CommitExe(e.into()))?;
+        let program_id = exe_commit_digest(&config, &expected_committed_exe);
+        let app_vm = (app_pk.app_fri_params(), &app_pk.app_vm_pk.vm_config);
+        if exe_commit_digest(&config, &app_committed_exe) != program_id
+            || digest != program_id.as_bytes()
+            || bincode::serialize(&app_vm).map_err(KeysError::from)?
+                != bincode::serialize(&(config.fri_params(), config.vm_config()))
+                    .map_err(KeysError::from)?
+        {
+            return Err(KeysError::ProgramMismatch(dir.to_path_buf()).into());
         }
+        let app_vk = app_pk.get_app_vk();
 
-        let app_config = AppConfig::new(FriParameters::standard_fast(), vm_cfg);
+        let agg_stark_pk = KeyCell::load(&dir.join(AGG_STARK_PROVING_KEY_FILE))?;
+        let agg_stark_vk = KeyCell::load(&dir.join(AGG_STARK_VERIFYING_KEY_FILE))?;
+        if let (Some(agg_stark_pk), None) = (agg_stark_pk.get(), agg_stark_vk.get()) {
+            agg_stark_vk.set(AggStarkVerifyingKey::new(&agg_stark_pk));
+        }
 
-        let app_pk = sdk.app_keygen(app_config).unwrap();
+        Ok(Self {
+            config,
+            app_exe,
+            app_pk: Arc::new(app_pk),
+            app_committed_exe: Arc::new(app_committed_exe),
+            app_vk,
+            program_id,
+            agg_stark_pk,
+            agg_stark_vk,
+            stark_prover: KeyCell::default(),
+            #[cfg(feature = "evm")]
+            evm_keys: evm::EvmKeys::load(dir)?,
+        })
+    }
 
-        let app_committed_exe = sdk
-            .commit_app_exe(app_pk.app_fri_params(), app_exe)
-            .unwrap();
+    /// Executes the program segment by segment like `Sdk::execute`, counting
+    /// the cycles of every segment along the way and stopping after the first
+    /// segment that runs into `limits`.
+    ///
+    /// The executor prints what the guest writes to the stdout of the host, so
+    /// it is captured from there and returned alongside the results, or in
+    /// [`ExecuteError::GuestPanicked`].
+    fn execute_metered(
+        &self,
+        stdin: StdIn,
+        limits: &ExecutionLimits,
+    ) -> Result<(Vec<F>, u64, GuestOutput), ExecuteError> {
+        let vm_config = self.config.vm_config();
+        let executor = VmExecutor::<F, SdkVmConfig>::new(vm_config.clone());
+        let start = Instant::now();
+        let mut total_num_cycles = 0;
+        let (result, guest_output) = capture_output(|| {
+            executor.execute_and_then(
+                self.app_exe.clone(),
+                stdin,
+                |_, segment| {
+                    total_num_cycles += segment.metrics.cycle_count as u64;
+                    if let Some(max_cycles) =
+                        limits.max_cycles.filter(|max| total_num_cycles > *max)
+                    {
+                        return Err(ExecuteError::CycleLimitExceeded { max_cycles });
+                    }
+                    let elapsed = start.elapsed();
+                    if let Some(max_duration) = limits.max_duration.filter(|max| elapsed > *max) {
+                        return Err(ExecuteError::Timeout(max_duration));
+                    }
+                    Ok(segment.final_memory)
+                },
+                |e: ExecutionError| match e {
+                    ExecutionError::FailedWithExitCode(exit_code) => ExecuteError::GuestPanicked {
+                        exit_code,
+                        output: GuestOutput::default(),
+                    },
+                    e => ExecuteError::Client(e.into()),
+                },
+            )
+        })
+        .map_err(ExecuteError::CaptureOutput)?;
+        let final_memories = result.map_err(|mut err| {
+            if let ExecuteError::GuestPanicked { output, .. } = &mut err {
+                *output = guest_output.clone();
+            }
+            err
+        })?;
 
+        // Only the last segment keeps the final memory, which holds the public values.
+        let final_memory = final_memories
+            .into_iter()
+            .last()
+            .flatten()
+            .ok_or(ExecuteError::MissingFinalMemory)?;
+        let public_values = extract_public_values(
+            &vm_config.system().memory_config.memory_dimensions(),
+            vm_config.system().num_public_values,
+            &final_memory,
+        );
+
+        Ok((public_values, total_num_cycles, guest_output))
+    }
+
+    /// Returns the aggregation proving key, generating it and its verifying
+    /// keys on first use.
+    fn agg_stark_pk(&self) -> Result<Arc<AggStarkProvingKey>, OpenVMError> {
+        Ok(self.agg_stark_pk.get_or_try_init(|| {
+            let agg_stark_pk = Sdk::new()
+                .agg_stark_keygen(self.config.agg_stark_config())
+                .map_err(|e| ProveError::AggKeygen(e.into()))?;
+            self.agg_stark_vk
+                .set(AggStarkVerifyingKey::new(&agg_stark_pk));
+            Ok::<_, ProveError>(agg_stark_pk)
+        })?)
+    }
+
+    /// Returns the prover of compressed proofs, building it on first use.
+    fn stark_prover(
+        &self,
+    ) -> Result<Arc<StarkProver<SdkVmConfig, BabyBearPoseidon2Engine>>, OpenVMError> {
+        self.stark_prover.get_or_try_init(|| {
+            // The prover takes the aggregation key by value, so it is copied
+            // once here rather than for every proof.
+            let agg_stark_pk = self.agg_stark_pk()?;
+            Ok(StarkProver::new(
+                Arc::clone(&self.app_pk),
+                Arc::clone(&self.app_committed_exe),
+                (*agg_stark_pk).clone(),
+                AggregationTreeConfig::default(),
+            ))
+        })
+    }
+
+    /// Commitments to the app exe and the leaf verifier, which an aggregated
+    /// proof is checked against.
+    fn app_execution_commit(&self) -> AppExecutionCommit {
+        AppExecutionCommit::compute(
+            &self.app_pk.app_vm_pk.vm_config,
+            &self.app_committed_exe,
+            &self.app_pk.leaf_committed_exe,
+        )
+    }
+
+    /// Proves the execution as an app proof, with one STARK proof per segment.
+    fn prove_app(&self, stdin: StdIn) -> Result<(PublicValues, Vec<u8>), OpenVMError> {
         let prover = AppProver::<_, BabyBearPoseidon2Engine>::new(
-            app_pk.app_vm_pk.clone(),
-            app_committed_exe,
+            Arc::clone(&self.app_pk.app_vm_pk),
+            Arc::clone(&self.app_committed_exe),
         );
-        let now = std::time::Instant::now();
         let proof = prover.generate_app_proof(stdin);
+
+        let public_values = public_values_to_bytes(&proof.user_public_values.public_values);
+        let proof_bytes = proof
+            .encode_to_vec()
+            .map_err(|e| ProveError::Encode(e.into()))?;
+        Ok((public_values, proof_bytes))
+    }
+
+    /// Proves the execution and aggregates the app proof into a single STARK
+    /// proof.
+    fn prove_stark(&self, stdin: StdIn) -> Result<(PublicValues, Vec<u8>), OpenVMError> {
+        let proof = self.stark_prover()?.generate_e2e_stark_proof(stdin);
+
+        let public_values = public_values_to_bytes(&proof.user_public_values);
+        let proof_bytes = proof
+            .encode_to_vec()
+            .map_err(|e| ProveError::Encode(e.into()))?;
+        Ok((public_values, proof_bytes))
+    }
+
+    fn verify_stark_proof(&self, mut proof: &[u8]) -> Result<PublicValues, OpenVMError> {
+        let proof = VmStarkProof::<BabyBearPoseidon2Config>::decode(&mut proof)
+            .map_err(|e| VerifyError::Decode(e.into()))?;
+
+        let agg_stark_vk = self
+            .agg_stark_vk
+            .get()
+            .ok_or(VerifyError::MissingVerifyingKey(ProofKind::Compressed))?;
+        let proven = agg_stark_vk.verify(&proof)?;
+
+        // The aggregated proof is checked against the commitments of this
+        // program's exe and VM, which binds it to the program.
+        let commit = self.app_execution_commit();
+        if proven.app_exe_commit != commit.app_exe_commit
+            || proven.app_vm_commit != commit.app_vm_commit
+        {
+            return Err(VerifyError::AppCommitMismatch.into());
+        }
+
+        Ok(public_values_to_bytes(&proof.user_public_values))
+    }
+}
+
+/// Commitment to the program, its initial memory and its entry point, as
+/// returned by verifying an app proof of it, narrowed into the bytes of a
+/// [`ProgramDigest`].
+fn exe_commit_digest(
+    config: &OpenVMConfig,
+    app_committed_exe: &NonRootCommittedExe,
+) -> ProgramDigest {
+    let memory_config = &config.vm_config().system().memory_config;
+    let commit: [F; 8] = app_committed_exe.compute_exe_commit(memory_config).into();
+    field_digest(commit)
+}
+
+fn field_digest(commit: [F; 8]) -> ProgramDigest {
+    let mut bytes = [0; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(4).zip(commit) {
+        chunk.copy_from_slice(&limb.as_canonical_u32().to_le_bytes());
+    }
+    ProgramDigest::new(bytes)
+}
+
+/// Checks that a verified proof is of the program `program_id` identifies.
+/// Neither the app verifying key, which is shared by every program of the same
+/// VM config, nor the proof envelope bind the program, so this is what does.
+fn ensure_exe_commit(exe_commit: [F; 8], program_id: &ProgramDigest) -> Result<(), VerifyError> {
+    let actual = field_digest(exe_commit);
+    if actual != *program_id {
+        return Err(VerifyError::ProgramMismatch {
+            expected: *program_id,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// A key that is generated at most once, on first use, unless it was loaded.
+struct KeyCell<T>(Mutex<Option<Arc<T>>>);
+
+impl<T> Default for KeyCell<T> {
+    fn default() -> Self {
+        Self(Mutex::new(None))
+    }
+}
+
+impl<T> KeyCell<T> {
+    fn get(&self) -> Option<Arc<T>> {
+        self.lock().clone()
+    }
+
+    fn set(&self, key: T) {
+        *self.lock() = Some(Arc::new(key));
+    }
+
+    /// Returns the key, generating it with `keygen` if there is none yet. The
+    /// lock is held during keygen, so concurrent callers wait for the key
+    /// instead of generating it again.
+    fn get_or_try_init<E>(&self, keygen: impl FnOnce() -> Result<T, E>) -> Result<Arc<T>, E> {
+        let mut key = self.lock();
+        if let Some(key) = key.as_ref() {
+            return Ok(key.clone());
+        }
+        let generated = Arc::new(keygen()?);
+        *key = Some(generated.clone());
+        Ok(generated)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Arc<T>>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: serde::Serialize + serde::de::DeserializeOwned> KeyCell<T> {
+    /// Writes the key to `path` if there is one.
+    fn export(&self, path: &Path) -> Result<(), KeysError> {
+        match self.get() {
+            Some(key) => write_key_file(path, &bincode::serialize(&*key)?),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the key from `path` if the file exists.
+    fn load(path: &Path) -> Result<Self, KeysError> {
+        let cell = Self::default();
+        if path.exists() {
+            cell.set(bincode::deserialize(&read_key_file(path)?)?);
+        }
+        Ok(cell)
+    }
+}
+
+fn write_key_file(path: &Path, contents: &[u8]) -> Result<(), KeysError> {
+    fs::write(path, contents).map_err(|source| KeysError::io(path, source))
+}
+
+fn read_key_file(path: &Path) -> Result<Vec<u8>, KeysError> {
+    fs::read(path).map_err(|source| KeysError::io(path, source))
+}
+
+/// OpenVM reveals public values one byte per field element, so each element is
+/// narrowed back into the byte it holds.
+fn public_values_to_bytes(public_values: &[F]) -> PublicValues {
+    public_values
+        .iter()
+        .map(|value| value.as_canonical_u32() as u8)
+        .collect()
+}
+
+/// Reads a file into one field element per byte, as `StdIn::write_bytes` lays
+/// it out, without holding the bytes in between.
+fn read_field_elements(path: &Path) -> Result<Vec<F>, InputError> {
+    let (file, len) = open_input_file(path)?;
+    let mut elements = Vec::with_capacity(len as usize);
+    for byte in io::BufReader::new(file).bytes() {
+        let byte = byte.map_err(|source| InputError::ReadFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        elements.push(F::from_canonical_u8(byte));
+    }
+    Ok(elements)
+}
+
+fn stdin_from_inputs(inputs: &Input) -> Result<StdIn, InputError> {
+    let mut stdin = StdIn::default();
+    for input in inputs.iter() {
+        match input {
+            InputItem::Object(serialize) => stdin.write(serialize),
+            InputItem::Bytes(items) => stdin.write_bytes(items),
+            InputItem::File(path) => stdin.buffer.push_back(read_field_elements(path)?),
+            InputItem::Encoded(obj) => stdin.write_bytes(obj.encoded(InputEncoding::Words)),
+        }
+    }
+    Ok(stdin)
+}
+
+impl zkVM for EreOpenVM {
+    fn execute_with_limits(
+        &self,
+        inputs: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, zkvm_interface::ProgramExecutionReport), zkVMError> {
+        let stdin = stdin_from_inputs(inputs)?;
+
+        let start = Instant::now();
+        let (public_values, total_num_cycles, guest_output) = self
+            .execute_metered(stdin, limits)
+            .map_err(OpenVMError::from)?;
+
+        Ok((
+            public_values_to_bytes(&public_values),
+            ProgramExecutionReport {
+                total_num_cycles,
+                execution_duration: start.elapsed(),
+                guest_output,
+                ..Default::default()
+            },
+        ))
+    }
+
+    fn prove_with_limits(
+        &self,
+        inputs: &Input,
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
+        let prove = match proof_kind {
+            ProofKind::Core => Self::prove_app,
+            ProofKind::Compressed => Self::prove_stark,
+            // The halo2 proof wrapping the aggregated STARK proof for the EVM.
+            #[cfg(feature = "evm")]
+            ProofKind::Halo2 => Self::prove_evm,
+            _ => {
+                return Err(zkVMError::UnsupportedProofKind {
+                    zkvm: NAME,
+                    proof_kind,
+                });
+            }
+        };
+
+        // The provers have no limits of their own, and panic if the guest fails,
+        // so execute the program before proving it.
+        self.execute_with_limits(inputs, limits)?;
+
+        let stdin = stdin_from_inputs(inputs)?;
+
+        let now = std::time::Instant::now();
+        let (public_values, proof_bytes) = prove(self, stdin)?;
         let elapsed = now.elapsed();
 
-        let proof_bytes = proof.encode_to_vec().unwrap();
+        let envelope = ProofEnvelope::new(
+            NAME,
+            SDK_VERSION,
+            self.program_id(),
+            proof_kind,
+            proof_bytes,
+        );
 
-        Ok((proof_bytes, ProgramProvingReport::new(elapsed)))
+        Ok((
+            public_values,
+            envelope.encode(),
+            ProgramProvingReport::new(elapsed),
+        ))
     }
 
-    fn verify(&self, mut proof: &[u8]) -> Result<(), zkVMError> {
-        let sdk = Sdk::new();
-        let vm_cfg = SdkVmConfig::builder()
-            .system(Default::default())
-            .rv32i(Default::default())
-            .rv32m(Default::default())
-            .io(Default::default())
-            .build();
+    fn program_id(&self) -> ProgramDigest {
+        self.program_id
+    }
 
-        let app_config = AppConfig::new(FriParameters::standard_fast(), vm_cfg);
+    fn input_encoding(&self) -> InputEncoding {
+        InputEncoding::Words
+    }
+}
 
-        let app_pk = sdk.app_keygen(app_config).unwrap();
+impl zkVMVerifier for EreOpenVM {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        // The program digest of the envelope is written by the prover, so the
+        // program is bound by the commitments each kind of proof is checked
+        // against instead.
+        let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+        match envelope.proof_kind {
+            ProofKind::Core => verify_app_proof(&self.app_vk, &self.program_id, &envelope.payload),
+            ProofKind::Compressed => Ok(self.verify_stark_proof(&envelope.payload)?),
+            #[cfg(feature = "evm")]
+            ProofKind::Halo2 => Ok(self.verify_evm_proof(&envelope.payload)?),
+            proof_kind => Err(zkVMError::UnsupportedProofKind {
+                zkvm: NAME,
+                proof_kind,
+            }),
+        }
+    }
 
-        let proof = ContinuationVmProof::<BabyBearPoseidon2Config>::decode(&mut proof).unwrap();
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::WordAlignedBincode
+    }
 
-        let app_vk = app_pk.get_app_vk();
-        sdk.verify_app_proof(&app_vk, &proof)
-            .map(|_payload| ())
-            .map_err(|e| OpenVMError::Verify(VerifyError::Client(e.into())))
-            .map_err(zkVMError::from)
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+}
+
+/// Verifier-only handle for app proofs of a single OpenVM program, built from
+/// its app verifying key and exe commitment alone.
+pub struct EreOpenVMVerifier {
+    app_vk: AppVerifyingKey,
+    program_id: ProgramDigest,
+}
+
+impl EreOpenVMVerifier {
+    /// Creates a verifier from a `bincode`-encoded app verifying key, as
+    /// returned by [`EreOpenVM::app_vk_bytes`], and the program ID of the
+    /// program whose proofs it accepts, as returned by [`zkVM::program_id`].
+    ///
+    /// The app verifying key is the same for every program built with the same
+    /// VM config, so proofs are checked against `program_id` as well.
+    pub fn from_app_vk(app_vk: &[u8], program_id: ProgramDigest) -> Result<Self, OpenVMError> {
+        let app_vk = bincode::deserialize(app_vk).map_err(VerifyError::AppVerifyingKey)?;
+        Ok(Self { app_vk, program_id })
+    }
+}
+
+impl zkVMVerifier for EreOpenVMVerifier {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+        // Aggregated proofs are verified against the aggregation keys, which
+        // the app verifying key knows nothing about.
+        if envelope.proof_kind != ProofKind::Core {
+            return Err(zkVMError::UnsupportedProofKind {
+                zkvm: NAME,
+                proof_kind: envelope.proof_kind,
+            });
+        }
+        verify_app_proof(&self.app_vk, &self.program_id, &envelope.payload)
+    }
+
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::WordAlignedBincode
     }
 
     fn name(&self) -> &'static str {
@@ -174,6 +699,22 @@ impl zkVM for EreOpenVM {
     }
 }
 
+fn verify_app_proof(
+    app_vk: &AppVerifyingKey,
+    program_id: &ProgramDigest,
+    mut proof: &[u8],
+) -> Result<PublicValues, zkVMError> {
+    let proof = ContinuationVmProof::<BabyBearPoseidon2Config>::decode(&mut proof)
+        .map_err(|e| OpenVMError::Verify(VerifyError::Decode(e.into())))?;
+
+    let payload = Sdk::new()
+        .verify_app_proof(app_vk, &proof)
+        .map_err(|e| OpenVMError::Verify(VerifyError::Client(e.into())))?;
+    ensure_exe_commit(payload.exe_commit, program_id).map_err(OpenVMError::Verify)?;
+
+    Ok(public_values_to_bytes(&payload.user_public_values))
+}
+
 #[cfg(test)]
 mod tests {
     use zkvm_interface::Compiler;
@@ -181,63 +722,304 @@ mod tests {
     use crate::OPENVM_TARGET;
 
     use super::*;
-    use std::path::PathBuf;
+    use serde::de::DeserializeOwned;
+    use std::{collections::VecDeque, path::PathBuf};
+    use zkvm_interface::test_utils::{GuestInputReader, check_input_parity, check_prove_verify};
 
     // TODO: for now, we just get one test file
     // TODO: but this should get the whole directory and compile each test
     fn get_compile_test_guest_program_path() -> PathBuf {
+        get_test_guest_program_path("basic")
+    }
+
+    fn get_test_guest_program_path(name: &str) -> PathBuf {
         let workspace_dir = env!("CARGO_WORKSPACE_DIR");
         PathBuf::from(workspace_dir)
             .join("tests")
             .join("openvm")
             .join("compile")
-            .join("basic")
+            .join(name)
             .canonicalize()
-            .expect("Failed to find or canonicalize test guest program at <CARGO_WORKSPACE_DIR>/tests/compile/openvm")
+            .unwrap_or_else(|_| {
+                panic!("Failed to find or canonicalize test guest program at <CARGO_WORKSPACE_DIR>/tests/openvm/compile/{name}")
+            })
+    }
+
+    /// `openvm::io::read_vec` takes the next frame of `StdIn` as bytes, and
+    /// `openvm::io::read` decodes its words with `openvm::serde`.
+    struct OpenVMGuest(VecDeque<Vec<F>>);
+
+    impl GuestInputReader for OpenVMGuest {
+        fn read<T: DeserializeOwned>(&mut self) -> T {
+            let words: Vec<u32> = self
+                .read_bytes()
+                .chunks(4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+                .collect();
+            openvm::serde::from_slice::<T, u32>(&words).unwrap()
+        }
+
+        fn read_bytes(&mut self) -> Vec<u8> {
+            let frame = self.0.pop_front().expect("no frame left");
+            frame
+                .into_iter()
+                .map(|byte| u8::try_from(byte.as_canonical_u32()).unwrap())
+                .collect()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+    }
+
+    #[test]
+    fn test_input_parity() {
+        check_input_parity(InputEncoding::Words, |input| {
+            OpenVMGuest(stdin_from_inputs(input).unwrap().buffer)
+        });
     }
 
     #[test]
     fn test_compile() {
         let test_guest_path = get_compile_test_guest_program_path();
-        let elf = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
         assert!(
-            !elf.instructions.is_empty(),
+            !program.elf.instructions.is_empty(),
             "ELF bytes should not be empty."
         );
     }
 
     #[test]
-    #[should_panic]
-    fn test_execute_empty_input_panic() {
-        // Panics because the program expects input arguments, but we supply none
+    fn test_execute_empty_input() {
+        // Fails because the program expects input arguments, but we supply none
         let test_guest_path = get_compile_test_guest_program_path();
-        let elf = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
         let empty_input = Input::new();
-        let zkvm = EreOpenVM::new(elf, ProverResourceType::Cpu);
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
 
-        zkvm.execute(&empty_input).unwrap();
+        assert!(matches!(
+            zkvm.execute(&empty_input),
+            Err(zkVMError::GuestPanicked { .. })
+        ));
+        for proof_kind in [ProofKind::Core, ProofKind::Compressed] {
+            assert!(matches!(
+                zkvm.prove(&empty_input, proof_kind),
+                Err(zkVMError::GuestPanicked { .. })
+            ));
+        }
+        // Unsupported proof kinds are rejected before executing the program.
+        assert!(matches!(
+            zkvm.prove(&empty_input, ProofKind::Groth16),
+            Err(zkVMError::UnsupportedProofKind { .. })
+        ));
     }
 
     #[test]
     fn test_execute() {
         let test_guest_path = get_compile_test_guest_program_path();
-        let elf = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
         let mut input = Input::new();
         input.write(10u64);
 
-        let zkvm = EreOpenVM::new(elf, ProverResourceType::Cpu);
-        zkvm.execute(&input).unwrap();
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
+        let (public_values, report) = zkvm.execute(&input).unwrap();
+        assert!(report.total_num_cycles > 0);
+
+        // The guest reveals the 10th Fibonacci number as two `u32` limbs.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<u64>().unwrap(), 55);
+    }
+
+    #[test]
+    #[ignore = "libtest captures what the executor prints, run with --nocapture --test-threads=1"]
+    fn test_execute_guest_output() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u64);
+
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
+        let (_, report) = zkvm.execute(&input).unwrap();
+        assert_eq!(report.guest_output.stdout, b"fib(10) = 55\n");
+
+        match zkvm.execute(&Input::new()) {
+            Err(zkVMError::GuestPanicked {
+                message: Some(message),
+                output,
+                ..
+            }) => assert_eq!(output.panic_message(), Some(message)),
+            other => panic!("expected a guest panic with a message, got {other:?}"),
+        }
     }
 
     #[test]
     fn test_prove_verify() {
         let test_guest_path = get_compile_test_guest_program_path();
-        let elf = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
         let mut input = Input::new();
         input.write(10u64);
 
-        let zkvm = EreOpenVM::new(elf, ProverResourceType::Cpu);
-        let (proof, _) = zkvm.prove(&input).unwrap();
-        zkvm.verify(&proof).expect("proof should verify");
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
+        let (public_values, _) = check_prove_verify(&zkvm, &input, ProofKind::Core);
+
+        // The guest reveals the 10th Fibonacci number as two `u32` limbs.
+        assert_eq!(public_values[..8], 55u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_prove_verify_compressed() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u64);
+
+        let zkvm = EreOpenVM::new(program.clone(), ProverResourceType::Cpu);
+        let (public_values, proof) = check_prove_verify(&zkvm, &input, ProofKind::Compressed);
+        assert_eq!(public_values[..8], 55u64.to_le_bytes());
+
+        // Without the aggregation verifying keys the proof cannot be verified,
+        // and verifying does not generate them.
+        let fresh = EreOpenVM::new(program.clone(), ProverResourceType::Cpu);
+        assert!(matches!(
+            fresh.verify(&proof),
+            Err(zkVMError::ResourceUnavailable(_))
+        ));
+
+        // The exported verifying keys are enough, without the proving key.
+        let keys_dir = tempfile::tempdir().unwrap();
+        zkvm.export_keys(keys_dir.path()).unwrap();
+        fs::remove_file(keys_dir.path().join(AGG_STARK_PROVING_KEY_FILE)).unwrap();
+        let loaded =
+            EreOpenVM::load_keys(program, ProverResourceType::Cpu, keys_dir.path()).unwrap();
+        assert_eq!(loaded.verify(&proof).unwrap(), public_values);
+
+        // The app verifying key alone cannot verify aggregated proofs.
+        let verifier =
+            EreOpenVMVerifier::from_app_vk(&zkvm.app_vk_bytes().unwrap(), zkvm.program_id())
+                .unwrap();
+        assert!(matches!(
+            verifier.verify(&proof),
+            Err(zkVMError::UnsupportedProofKind {
+                proof_kind: ProofKind::Compressed,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg(not(feature = "evm"))]
+    fn test_prove_evm_requires_feature() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u64);
+
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
+        for proof_kind in [ProofKind::Halo2, ProofKind::Plonk] {
+            assert!(matches!(
+                zkvm.prove(&input, proof_kind),
+                Err(zkVMError::UnsupportedProofKind { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_export_and_load_keys() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let keys_dir = tempfile::tempdir().unwrap();
+
+        let zkvm = EreOpenVM::new(program.clone(), ProverResourceType::Cpu);
+        zkvm.export_keys(keys_dir.path()).unwrap();
+
+        let mut input = Input::new();
+        input.write(10u64);
+
+        let loaded =
+            EreOpenVM::load_keys(program.clone(), ProverResourceType::Cpu, keys_dir.path())
+                .expect("keys should load for the program they were generated for");
+        assert_eq!(loaded.program_id(), zkvm.program_id());
+        let (public_values, proof, _) = loaded.prove(&input, ProofKind::Core).unwrap();
+        assert_eq!(zkvm.verify(&proof).unwrap(), public_values);
+
+        let mut other_program = program.clone();
+        other_program.elf.pc_start += 4;
+        assert!(matches!(
+            EreOpenVM::load_keys(other_program, ProverResourceType::Cpu, keys_dir.path()),
+            Err(OpenVMError::Keys(KeysError::ProgramMismatch(_)))
+        ));
+
+        // A committed exe of another program is rejected too.
+        let other = EreOpenVM::new(
+            OPENVM_TARGET::compile(&get_test_guest_program_path("square"))
+                .expect("compilation failed"),
+            ProverResourceType::Cpu,
+        );
+        fs::write(
+            keys_dir.path().join(APP_COMMITTED_EXE_FILE),
+            bincode::serialize(&*other.app_committed_exe).unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            EreOpenVM::load_keys(program, ProverResourceType::Cpu, keys_dir.path()),
+            Err(OpenVMError::Keys(KeysError::ProgramMismatch(_)))
+        ));
+    }
+
+    #[test]
+    fn test_verifier_from_app_vk() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let program = OPENVM_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u64);
+
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
+        let (public_values, proof, _) = zkvm.prove(&input, ProofKind::Core).unwrap();
+
+        let verifier =
+            EreOpenVMVerifier::from_app_vk(&zkvm.app_vk_bytes().unwrap(), zkvm.program_id())
+                .unwrap();
+        assert_eq!(verifier.verify(&proof).unwrap(), public_values);
+
+        assert!(EreOpenVMVerifier::from_app_vk(&[], zkvm.program_id()).is_err());
+    }
+
+    #[test]
+    fn test_verifier_rejects_other_program() {
+        let program = OPENVM_TARGET::compile(&get_compile_test_guest_program_path())
+            .expect("compilation failed");
+        let other_program = OPENVM_TARGET::compile(&get_test_guest_program_path("square"))
+            .expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u64);
+
+        let zkvm = EreOpenVM::new(program, ProverResourceType::Cpu);
+        let other = EreOpenVM::new(other_program, ProverResourceType::Cpu);
+        assert_ne!(other.program_id(), zkvm.program_id());
+        // Both guests are built with the default VM config, so they share the
+        // app verifying key.
+        assert_eq!(other.app_vk_bytes().unwrap(), zkvm.app_vk_bytes().unwrap());
+
+        let (_, proof, _) = other.prove(&input, ProofKind::Core).unwrap();
+        assert!(matches!(
+            zkvm.verify(&proof),
+            Err(zkVMError::InvalidProof(_))
+        ));
+        // Relabelling the envelope does not help, since the program is bound by
+        // the exe commitment of the proof.
+        let mut relabelled = ProofEnvelope::decode(&proof, NAME, SDK_VERSION).unwrap();
+        relabelled.program_id = zkvm.program_id();
+        assert!(matches!(
+            zkvm.verify(&relabelled.encode()),
+            Err(zkVMError::InvalidProof(_))
+        ));
+
+        let verifier =
+            EreOpenVMVerifier::from_app_vk(&zkvm.app_vk_bytes().unwrap(), zkvm.program_id())
+                .unwrap();
+        assert!(matches!(
+            verifier.verify(&proof),
+            Err(zkVMError::InvalidProof(_))
+        ));
     }
 }
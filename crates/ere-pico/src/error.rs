@@ -1,10 +1,28 @@
 use std::{io, path::PathBuf, process::ExitStatus};
 use thiserror::Error;
-use zkvm_interface::zkVMError;
+use zkvm_interface::{GuestOutput, zkVMError};
 
 impl From<PicoError> for zkVMError {
     fn from(value: PicoError) -> Self {
-        zkVMError::Other(Box::new(value))
+        match value {
+            err @ (PicoError::PathNotFound(_)
+            | PicoError::Spawn(_)
+            | PicoError::CargoFailed { .. }
+            | PicoError::ElfNotFound(_)
+            | PicoError::ReadElf { .. }
+            | PicoError::Setup(_)) => zkVMError::Compile(Box::new(err)),
+            PicoError::GuestPanicked(message) => zkVMError::GuestPanicked {
+                exit_code: None,
+                message: Some(message.clone()),
+                output: GuestOutput::default(),
+                source: Box::new(PicoError::GuestPanicked(message)),
+            },
+            err @ PicoError::DecodeProof(_) => zkVMError::ProofDecode(Box::new(err)),
+            err @ (PicoError::Verify { .. } | PicoError::PublicValuesDigest) => {
+                zkVMError::InvalidProof(Box::new(err))
+            }
+            err => zkVMError::Other(Box::new(err)),
+        }
     }
 }
 
@@ -33,4 +51,36 @@ pub enum PicoError {
         #[source]
         source: io::Error,
     },
+
+    /// Setting up the prover client for the ELF panicked.
+    #[error("failed to set up the Pico prover: {0}")]
+    Setup(String),
+
+    /// The emulator panicked on the guest failing.
+    #[error("guest panicked: {0}")]
+    GuestPanicked(String),
+
+    /// Proving failed.
+    #[error("failed to generate proof: {0}")]
+    Prove(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The proof could not be (de)serialized.
+    #[error("failed to (de)serialize proof: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    /// The proof payload is not a `bincode`-encoded pair of proofs.
+    #[error("failed to decode proof: {0}")]
+    DecodeProof(#[source] bincode::Error),
+
+    /// One of the proofs failed verification.
+    #[error("{proof} proof failed verification: {source}")]
+    Verify {
+        proof: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
+    /// The public values carried by the proof are not the ones it commits to.
+    #[error("public values do not match the digest committed by the proof")]
+    PublicValuesDigest,
 }
@@ -1,12 +1,26 @@
 use pico_sdk::client::DefaultProverClient;
-use std::{process::Command, time::Instant};
+use pico_vm::{
+    configs::stark_config::KoalaBearPoseidon2, emulator::stdin::EmulatorStdinBuilder,
+    machine::proof::MetaProof,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    process::Command,
+    sync::Arc,
+    time::Instant,
+};
 use zkvm_interface::{
-    Compiler, Input, InputItem, ProgramExecutionReport, ProgramProvingReport, ProverResourceType,
-    zkVM, zkVMError,
+    Compiler, ExecutionLimits, Input, InputEncoding, InputError, InputItem, MaxCyclesUnsupported,
+    ProgramDigest, ProgramExecutionReport, ProgramProvingReport, ProofEnvelope, ProofKind,
+    ProverResourceType, PublicValues, PublicValuesEncoding, read_input_file, zkVM, zkVMError,
+    zkVMVerifier,
 };
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
 mod error;
+mod verify;
 use error::PicoError;
 
 #[allow(non_camel_case_types)]
@@ -51,81 +65,171 @@ impl Compiler for PICO_TARGET {
     }
 }
 
+/// Proof produced by [`ErePico::prove`], carried as the payload of the
+/// [`ProofEnvelope`].
+#[derive(Serialize, Deserialize)]
+pub struct PicoProofWithPublicValues {
+    /// RISC-V proof of the execution, whose `pv_stream` holds the public values
+    /// committed by the guest.
+    pub riscv: MetaProof<KoalaBearPoseidon2>,
+    /// Recursive proof combining the shards of `riscv` into a single proof.
+    pub combine: MetaProof<KoalaBearPoseidon2>,
+}
+
 pub struct ErePico {
-    program: <PICO_TARGET as Compiler>::Program,
+    /// Prover client, which sets up the proving and verifying keys of the
+    /// program once on construction.
+    client: Arc<DefaultProverClient>,
+    /// blake3 hash of the program ELF.
+    program_id: ProgramDigest,
 }
 
 impl ErePico {
+    /// # Panics
+    ///
+    /// Panics if the prover client cannot be set up for the ELF, see
+    /// [`ErePico::try_new`].
     pub fn new(
         program_bytes: <PICO_TARGET as Compiler>::Program,
-        _resource_type: ProverResourceType,
+        resource_type: ProverResourceType,
     ) -> Self {
-        ErePico {
-            program: program_bytes,
-        }
+        Self::try_new(program_bytes, resource_type).expect("Failed to set up Pico prover")
     }
-}
-impl zkVM for ErePico {
-    fn execute(&self, inputs: &Input) -> Result<ProgramExecutionReport, zkVMError> {
-        let client = DefaultProverClient::new(&self.program);
 
-        let mut stdin = client.new_stdin_builder();
+    /// Like [`ErePico::new`], but returns an error instead of panicking if the
+    /// prover client cannot be set up, e.g. because the ELF is invalid.
+    pub fn try_new(
+        program_bytes: <PICO_TARGET as Compiler>::Program,
+        _resource_type: ProverResourceType,
+    ) -> Result<Self, PicoError> {
+        // `DefaultProverClient::new` panics instead of returning an error.
+        let client = panic::catch_unwind(|| DefaultProverClient::new(&program_bytes))
+            .map_err(|payload| PicoError::Setup(panic_message(payload)))?;
+        Ok(ErePico {
+            client: Arc::new(client),
+            program_id: ProgramDigest::new(*blake3::hash(&program_bytes).as_bytes()),
+        })
+    }
+
+    fn stdin_builder(
+        &self,
+        inputs: &Input,
+    ) -> Result<EmulatorStdinBuilder<Vec<u8>, KoalaBearPoseidon2>, InputError> {
+        let mut stdin = self.client.new_stdin_builder();
         for input in inputs.iter() {
             match input {
                 InputItem::Object(serialize) => stdin.write(serialize),
                 InputItem::Bytes(items) => stdin.write_slice(items),
+                // The builder copies the slice, so the file is held twice until then.
+                InputItem::File(path) => stdin.write_slice(&read_input_file(path)?),
+                InputItem::Encoded(obj) => stdin.write_slice(obj.encoded(InputEncoding::Bincode)),
             }
         }
+        Ok(stdin)
+    }
+}
+impl zkVM for ErePico {
+    fn execute_with_limits(
+        &self,
+        inputs: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, ProgramExecutionReport), zkVMError> {
+        if limits.max_cycles.is_some() {
+            return Err(zkVMError::Unsupported(Box::new(MaxCyclesUnsupported {
+                zkvm: NAME,
+            })));
+        }
+        let stdin = self.stdin_builder(inputs)?;
 
+        let client = Arc::clone(&self.client);
         let start = Instant::now();
-        let emulation_result = client.emulate(stdin);
+        // The emulator panics instead of returning an error if the guest fails,
+        // and has no way to be stopped. On timeout the worker thread is detached
+        // and keeps emulating until the guest halts, which for a guest that
+        // never halts is never, as Pico cannot bound the cycles either.
+        let (total_num_cycles, public_values) = limits.run_with_deadline(move || {
+            panic::catch_unwind(AssertUnwindSafe(|| client.emulate(stdin)))
+                .map_err(|payload| PicoError::GuestPanicked(panic_message(payload)))
+        })??;
+        let execution_duration = start.elapsed();
 
-        Ok(ProgramExecutionReport {
-            total_num_cycles: emulation_result.0,
-            execution_duration: start.elapsed(),
-            ..Default::default()
-        })
+        Ok((
+            public_values,
+            ProgramExecutionReport {
+                total_num_cycles,
+                execution_duration,
+                ..Default::default()
+            },
+        ))
     }
 
-    fn prove(
+    fn prove_with_limits(
         &self,
         inputs: &Input,
-    ) -> Result<(Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
-        let client = DefaultProverClient::new(&self.program);
-
-        let mut stdin = client.new_stdin_builder();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => stdin.write(serialize),
-                InputItem::Bytes(items) => stdin.write_slice(items),
-            }
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
+        if proof_kind != ProofKind::Compressed {
+            return Err(zkVMError::UnsupportedProofKind {
+                zkvm: NAME,
+                proof_kind,
+            });
         }
+
+        // The prover has no limits of its own, and panics if the guest fails,
+        // so execute the program before proving it.
+        self.execute_with_limits(inputs, limits)?;
+
+        let stdin = self.stdin_builder(inputs)?;
         let now = std::time::Instant::now();
-        let meta_proof = client.prove(stdin).expect("Failed to generate proof");
+        let (riscv, combine) = panic::catch_unwind(AssertUnwindSafe(|| self.client.prove(stdin)))
+            .map_err(|payload| PicoError::Prove(panic_message(payload).into()))?
+            .map_err(|err| PicoError::Prove(err.into()))?;
         let elapsed = now.elapsed();
 
-        let mut proof_serialized = Vec::new();
-        for p in meta_proof.0.proofs().iter() {
-            bincode::serialize_into(&mut proof_serialized, p).unwrap();
-        }
-        for p in meta_proof.1.proofs().iter() {
-            bincode::serialize_into(&mut proof_serialized, p).unwrap();
-        }
+        // The public values committed by the guest are carried by the RISC-V proof.
+        let public_values = riscv.pv_stream.clone().unwrap_or_default();
 
-        for p in meta_proof.0.pv_stream.iter() {
-            bincode::serialize_into(&mut proof_serialized, p).unwrap();
-        }
-        for p in meta_proof.1.pv_stream.iter() {
-            bincode::serialize_into(&mut proof_serialized, p).unwrap();
-        }
+        let proof = PicoProofWithPublicValues { riscv, combine };
+        let payload = bincode::serialize(&proof).map_err(PicoError::from)?;
+
+        let envelope = ProofEnvelope::new(NAME, SDK_VERSION, self.program_id, proof_kind, payload);
+
+        Ok((
+            public_values,
+            envelope.encode(),
+            ProgramProvingReport::new(elapsed),
+        ))
+    }
+
+    fn program_id(&self) -> ProgramDigest {
+        self.program_id
+    }
+
+    fn input_encoding(&self) -> InputEncoding {
+        InputEncoding::Bincode
+    }
+}
+
+impl zkVMVerifier for ErePico {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+        envelope.ensure_program(&self.program_id)?;
+
+        let proof: PicoProofWithPublicValues =
+            bincode::deserialize(&envelope.payload).map_err(PicoError::DecodeProof)?;
 
-        Ok((proof_serialized, ProgramProvingReport::new(elapsed)))
+        let riscv_vk = self.client.riscv_vk();
+        verify::verify_riscv(&proof.riscv, riscv_vk)?;
+        verify::verify_combine(&proof.combine, riscv_vk)?;
+
+        let public_values = proof.riscv.pv_stream.clone().unwrap_or_default();
+        verify::verify_public_values(&proof.riscv, &public_values)?;
+        Ok(public_values)
     }
 
-    fn verify(&self, _proof: &[u8]) -> Result<(), zkVMError> {
-        let client = DefaultProverClient::new(&self.program);
-        let _vk = client.riscv_vk();
-        todo!("Verification method missing from sdk")
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Bincode
     }
 
     fn name(&self) -> &'static str {
@@ -137,11 +241,25 @@ impl zkVM for ErePico {
     }
 }
 
+/// Extracts the message of a caught panic, which is a `&str` or a `String`
+/// when raised by `panic!`.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::PICO_TARGET;
-    use std::path::PathBuf;
-    use zkvm_interface::Compiler;
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use std::{path::PathBuf, time::Duration};
+    use zkvm_interface::{
+        ProofEnvelopeError,
+        test_utils::{GuestInputReader, check_input_parity, check_prove_verify},
+    };
 
     fn get_compile_test_guest_program_path() -> PathBuf {
         let workspace_dir = env!("CARGO_WORKSPACE_DIR");
@@ -179,4 +297,122 @@ mod tests {
             }
         }
     }
+
+    /// `pico_sdk::io::read_vec` takes the next item of the stdin builder, and
+    /// `pico_sdk::io::read_as` decodes it with `bincode`.
+    struct PicoGuest(std::vec::IntoIter<Vec<u8>>);
+
+    impl GuestInputReader for PicoGuest {
+        fn read<T: DeserializeOwned>(&mut self) -> T {
+            bincode::deserialize(&self.read_bytes()).unwrap()
+        }
+
+        fn read_bytes(&mut self) -> Vec<u8> {
+            self.0.next().expect("no item left")
+        }
+
+        fn is_empty(&self) -> bool {
+            self.0.len() == 0
+        }
+    }
+
+    #[test]
+    fn test_input_parity() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let elf = PICO_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let zkvm = ErePico::new(elf, ProverResourceType::Cpu);
+
+        check_input_parity(InputEncoding::Bincode, |input| {
+            PicoGuest(zkvm.stdin_builder(input).unwrap().buffer.into_iter())
+        });
+    }
+
+    #[test]
+    fn test_try_new_invalid_elf() {
+        assert!(matches!(
+            ErePico::try_new(b"not an ELF".to_vec(), ProverResourceType::Cpu),
+            Err(PicoError::Setup(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        // Fails because the program expects an input, but we supply none.
+        let test_guest_path = get_compile_test_guest_program_path();
+        let elf = PICO_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let zkvm = ErePico::new(elf, ProverResourceType::Cpu);
+
+        assert!(matches!(
+            zkvm.execute(&Input::new()),
+            Err(zkVMError::GuestPanicked { .. })
+        ));
+        assert!(matches!(
+            zkvm.prove(&Input::new(), ProofKind::Compressed),
+            Err(zkVMError::GuestPanicked { .. })
+        ));
+    }
+
+    #[test]
+    fn test_execute_limits() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let elf = PICO_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u32);
+
+        let zkvm = ErePico::new(elf, ProverResourceType::Cpu);
+        let limits = ExecutionLimits::new().with_max_duration(Duration::from_nanos(1));
+        assert!(matches!(
+            zkvm.execute_with_limits(&input, &limits),
+            Err(zkVMError::Timeout(_))
+        ));
+
+        // The emulator cannot be stopped after a number of cycles.
+        let limits = ExecutionLimits::new().with_max_cycles(u64::MAX);
+        assert!(matches!(
+            zkvm.execute_with_limits(&input, &limits),
+            Err(zkVMError::Unsupported(_))
+        ));
+        assert!(matches!(
+            zkvm.prove_with_limits(&input, ProofKind::Compressed, &limits),
+            Err(zkVMError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_prove_verify() {
+        let test_guest_path = get_compile_test_guest_program_path();
+        let elf = PICO_TARGET::compile(&test_guest_path).expect("compilation failed");
+        let mut input = Input::new();
+        input.write(10u32);
+
+        let zkvm = ErePico::new(elf, ProverResourceType::Cpu);
+        let (public_values, proof) = check_prove_verify(&zkvm, &input, ProofKind::Compressed);
+
+        // The guest commits `FibonacciData { a, b, n }` for the 10th iteration.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<(u32, u32, u32)>().unwrap(), (55, 89, 10));
+
+        // The public values travel next to the proof, so swapping them has to be
+        // caught by the digest the proof commits to.
+        let mut tampered_envelope = ProofEnvelope::decode(&proof, NAME, SDK_VERSION).unwrap();
+        let mut tampered: PicoProofWithPublicValues =
+            bincode::deserialize(&tampered_envelope.payload).unwrap();
+        let mut unexpected_public_values = public_values.clone();
+        unexpected_public_values[0] ^= 1;
+        tampered.riscv.pv_stream = Some(unexpected_public_values);
+        tampered_envelope.payload = bincode::serialize(&tampered).unwrap();
+        assert!(matches!(
+            zkvm.verify(&tampered_envelope.encode()),
+            Err(zkVMError::InvalidProof(_))
+        ));
+
+        let mut foreign_envelope = ProofEnvelope::decode(&proof, NAME, SDK_VERSION).unwrap();
+        foreign_envelope.program_id = ProgramDigest::new([0; 32]);
+        assert!(matches!(
+            zkvm.verify(&foreign_envelope.encode()),
+            Err(zkVMError::ProofEnvelope(
+                ProofEnvelopeError::ProgramMismatch { .. }
+            ))
+        ));
+    }
 }
@@ -0,0 +1,91 @@
+use std::borrow::Borrow;
+
+use pico_vm::{
+    compiler::word::Word,
+    configs::{config::Val, stark_config::KoalaBearPoseidon2},
+    emulator::riscv::public_values::PublicValues,
+    instances::{
+        chiptype::{recursion_chiptype::RecursionChipType, riscv_chiptype::RiscvChipType},
+        machine::{combine::CombineMachine, riscv::RiscvMachine},
+    },
+    machine::{keys::BaseVerifyingKey, machine::MachineBehavior, proof::MetaProof},
+    primitives::consts::{COMBINE_DEGREE, RECURSION_NUM_PVS, RISCV_NUM_PVS},
+};
+use sha2::{Digest, Sha256};
+
+use crate::error::PicoError;
+
+type SC = KoalaBearPoseidon2;
+
+/// Verifies the RISC-V proof against the verifying key of the program.
+///
+/// Only the machine is needed for verification, so unlike the prover of the
+/// [`pico_sdk::client::DefaultProverClient`] this does not run any keygen.
+pub(crate) fn verify_riscv(
+    proof: &MetaProof<SC>,
+    riscv_vk: &BaseVerifyingKey<SC>,
+) -> Result<(), PicoError> {
+    let machine = RiscvMachine::new(
+        SC::new(),
+        RiscvChipType::<Val<SC>>::all_chips(),
+        RISCV_NUM_PVS,
+    );
+    machine
+        .verify(proof, riscv_vk)
+        .map_err(|err| PicoError::Verify {
+            proof: "riscv",
+            source: err.into(),
+        })
+}
+
+/// Verifies the combine proof, which recursively aggregates the RISC-V proof,
+/// against the verifying key of the program.
+pub(crate) fn verify_combine(
+    proof: &MetaProof<SC>,
+    riscv_vk: &BaseVerifyingKey<SC>,
+) -> Result<(), PicoError> {
+    let machine = CombineMachine::<_, _>::new(
+        SC::new(),
+        RecursionChipType::<Val<SC>, COMBINE_DEGREE>::all_chips(),
+        RECURSION_NUM_PVS,
+    );
+    machine
+        .verify(proof, riscv_vk)
+        .map_err(|err| PicoError::Verify {
+            proof: "combine",
+            source: err.into(),
+        })
+}
+
+/// Checks `public_values` against the digest committed by the RISC-V proof.
+///
+/// The `pv_stream` of the proof, which holds the public values themselves, is
+/// not covered by the proof. The guest-side `commit` hashes them with SHA-256
+/// into the committed value digest, which the last shard carries in its public
+/// values.
+pub(crate) fn verify_public_values(
+    proof: &MetaProof<SC>,
+    public_values: &[u8],
+) -> Result<(), PicoError> {
+    let shards = proof.proofs();
+    let last_shard = shards.last().ok_or(PicoError::PublicValuesDigest)?;
+    let num_public_values = size_of::<PublicValues<Word<u8>, u8>>();
+    let committed: &PublicValues<Word<Val<SC>>, Val<SC>> =
+        last_shard.public_values[..num_public_values].borrow();
+
+    let digest = Sha256::digest(public_values);
+    let expected = digest.chunks_exact(4).map(|word| {
+        Word::<Val<SC>>::from(u32::from_le_bytes(
+            word.try_into().expect("chunk of 4 bytes"),
+        ))
+    });
+    if !committed
+        .committed_value_digest
+        .iter()
+        .cloned()
+        .eq(expected)
+    {
+        return Err(PicoError::PublicValuesDigest);
+    }
+    Ok(())
+}
@@ -67,9 +67,20 @@ pub(crate) fn compile_risczero_program(path: &Path) -> Result<Risc0Program, Comp
             field: "elf_path",
             file: info_file.clone(),
         })?;
-    let image_id_hex_str = info_json["image_id_hex"].as_str().unwrap();
-    let image_id = hex::decode(image_id_hex_str).unwrap();
-    let image_id = image_id.try_into().unwrap();
+    let image_id_hex_str =
+        info_json["image_id_hex"]
+            .as_str()
+            .ok_or_else(|| CompileError::MissingJsonField {
+                field: "image_id_hex",
+                file: info_file.clone(),
+            })?;
+    let image_id = hex::decode(image_id_hex_str)
+        .ok()
+        .and_then(|image_id| Digest::try_from(image_id).ok())
+        .ok_or_else(|| CompileError::InvalidImageId {
+            image_id: image_id_hex_str.to_string(),
+            file: info_file.clone(),
+        })?;
 
     // Return Program
     fs::read(&elf_path)
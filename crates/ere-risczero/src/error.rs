@@ -1,10 +1,43 @@
 use std::{io, path::PathBuf, process::ExitStatus};
 use thiserror::Error;
+use zkvm_interface::zkVMError;
+
+impl From<RiscZeroError> for zkVMError {
+    fn from(value: RiscZeroError) -> Self {
+        match value {
+            err @ RiscZeroError::Compile(_) => zkVMError::Compile(Box::new(err)),
+            err @ RiscZeroError::Resource(_) => zkVMError::Unsupported(Box::new(err)),
+        }
+    }
+}
 
 #[derive(Debug, Error)]
 pub enum RiscZeroError {
     #[error(transparent)]
     Compile(#[from] CompileError),
+
+    #[error(transparent)]
+    Resource(#[from] ResourceError),
+}
+
+/// Errors that can be encountered while executing a RISC Zero program
+#[derive(Debug, Error)]
+pub enum ExecuteError {
+    #[error("Guest exited with non-zero exit code {0}")]
+    NonZeroExitCode(u32),
+    #[error("Guest exceeded the session limit")]
+    SessionLimitExceeded,
+}
+
+/// Errors that can be encountered while selecting the prover resource
+#[derive(Debug, Error)]
+pub enum ResourceError {
+    #[error("CPU mode requires both 'cuda' and 'metal' features to be disabled")]
+    CpuWithGpuFeature,
+    #[error("GPU selected but neither 'cuda' nor 'metal' feature is enabled")]
+    GpuWithoutGpuFeature,
+    #[error("Network proving not yet implemented for RISC Zero. Use CPU or GPU resource type.")]
+    NetworkUnsupported,
 }
 
 #[derive(Debug, Error)]
@@ -34,6 +67,8 @@ pub enum CompileError {
     },
     #[error("Could not find field `{field}` in JSON file `{file}`")]
     MissingJsonField { field: &'static str, file: PathBuf },
+    #[error("Invalid image ID `{image_id}` in JSON file `{file}`")]
+    InvalidImageId { image_id: String, file: PathBuf },
 }
 
 impl CompileError {
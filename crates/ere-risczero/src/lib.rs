@@ -1,10 +1,19 @@
-use std::time::Instant;
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+    time::Instant,
+};
 
 use compile::compile_risczero_program;
-use risc0_zkvm::{ExecutorEnv, ProverOpts, Receipt, default_executor, default_prover};
+pub use risc0_zkvm::Digest;
+use risc0_zkvm::{
+    ExecutorEnv, ExecutorImpl, ExitCode, NullSegmentRef, ProverOpts, Receipt, default_prover,
+};
 use zkvm_interface::{
-    Compiler, Input, InputItem, ProgramExecutionReport, ProgramProvingReport, ProverResourceType,
-    zkVM, zkVMError,
+    Compiler, ExecutionLimits, GuestOutput, Input, InputEncoding, InputError, InputItem,
+    ProgramDigest, ProgramExecutionReport, ProgramProvingReport, ProofEnvelope, ProofKind,
+    ProverResourceType, PublicValues, PublicValuesEncoding, open_input_file, zkVM, zkVMError,
+    zkVMVerifier,
 };
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
@@ -13,7 +22,7 @@ mod compile;
 pub use compile::Risc0Program;
 
 mod error;
-use error::RiscZeroError;
+use error::{ExecuteError, ResourceError, RiscZeroError};
 
 #[allow(non_camel_case_types)]
 pub struct RV32_IM_RISCZERO_ZKVM_ELF;
@@ -29,30 +38,42 @@ impl Compiler for RV32_IM_RISCZERO_ZKVM_ELF {
 }
 
 impl EreRisc0 {
+    /// # Panics
+    ///
+    /// Panics if the resource type is not supported by this build, see
+    /// [`EreRisc0::try_new`].
     pub fn new(
         program: <RV32_IM_RISCZERO_ZKVM_ELF as Compiler>::Program,
         resource_type: ProverResourceType,
     ) -> Self {
+        Self::try_new(program, resource_type).expect("Unsupported RISC Zero resource type")
+    }
+
+    /// Like [`EreRisc0::new`], but returns an error instead of panicking if the
+    /// resource type does not match the enabled `cuda`/`metal` features, or is
+    /// network proving, which is not implemented yet.
+    pub fn try_new(
+        program: <RV32_IM_RISCZERO_ZKVM_ELF as Compiler>::Program,
+        resource_type: ProverResourceType,
+    ) -> Result<Self, RiscZeroError> {
         match resource_type {
             ProverResourceType::Cpu => {
                 #[cfg(any(feature = "cuda", feature = "metal"))]
-                panic!("CPU mode requires both 'cuda' and 'metal' features to be disabled");
+                return Err(ResourceError::CpuWithGpuFeature.into());
             }
             ProverResourceType::Gpu => {
                 #[cfg(not(any(feature = "cuda", feature = "metal")))]
-                panic!("GPU selected but neither 'cuda' nor 'metal' feature is enabled");
+                return Err(ResourceError::GpuWithoutGpuFeature.into());
             }
             ProverResourceType::Network(_) => {
-                panic!(
-                    "Network proving not yet implemented for RISC Zero. Use CPU or GPU resource type."
-                );
+                return Err(ResourceError::NetworkUnsupported.into());
             }
         }
 
-        Self {
+        Ok(Self {
             program,
             resource_type,
-        }
+        })
     }
 }
 
@@ -63,65 +84,140 @@ pub struct EreRisc0 {
 }
 
 impl zkVM for EreRisc0 {
-    fn execute(&self, inputs: &Input) -> Result<ProgramExecutionReport, zkVMError> {
-        let executor = default_executor();
-        let mut env = ExecutorEnv::builder();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => {
-                    env.write(serialize).unwrap();
-                }
-                InputItem::Bytes(items) => {
-                    env.write_frame(items);
-                }
+    fn execute_with_limits(
+        &self,
+        inputs: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, ProgramExecutionReport), zkVMError> {
+        let start = Instant::now();
+        let mut guest_output = GuestOutput::default();
+        let mut timed_out = false;
+        let session = {
+            let env = executor_env(inputs, limits, &mut guest_output)?;
+            // The executor stops at the session limit itself, and calls back after
+            // every segment, where it is stopped once `max_duration` has passed.
+            ExecutorImpl::from_elf(env, &self.program.elf).and_then(|mut executor| {
+                executor.run_with_callback(|_| {
+                    if limits.max_duration.is_some_and(|max| start.elapsed() > max) {
+                        timed_out = true;
+                        anyhow::bail!("execution stopped at the deadline");
+                    }
+                    Ok(Box::new(NullSegmentRef))
+                })
+            })
+        };
+        if let Some(max_duration) = limits.max_duration.filter(|_| timed_out) {
+            return Err(zkVMError::Timeout(max_duration));
+        }
+        let session = session.map_err(|err| execution_error(err, guest_output.clone()))?;
+
+        match session.exit_code {
+            ExitCode::Halted(exit_code @ 1..) => {
+                return Err(zkVMError::GuestPanicked {
+                    exit_code: Some(exit_code as i32),
+                    message: guest_output.panic_message(),
+                    output: guest_output,
+                    source: Box::new(ExecuteError::NonZeroExitCode(exit_code)),
+                });
+            }
+            ExitCode::SessionLimit => {
+                return Err(zkVMError::CycleLimitExceeded(Box::new(
+                    ExecuteError::SessionLimitExceeded,
+                )));
             }
+            _ => {}
         }
-        let env = env.build().map_err(|err| zkVMError::Other(err.into()))?;
 
-        let start = Instant::now();
-        let session_info = executor
-            .execute(env, &self.program.elf)
-            .map_err(|err| zkVMError::Other(err.into()))?;
-        Ok(ProgramExecutionReport {
-            total_num_cycles: session_info.cycles() as u64,
+        let report = ProgramExecutionReport {
+            total_num_cycles: session.user_cycles,
             execution_duration: start.elapsed(),
+            guest_output,
             ..Default::default()
-        })
+        };
+
+        let public_values = session.journal.map(|journal| journal.bytes);
+        Ok((public_values.unwrap_or_default(), report))
     }
 
-    fn prove(&self, inputs: &Input) -> Result<(Vec<u8>, ProgramProvingReport), zkVMError> {
-        let prover = default_prover();
-        let mut env = ExecutorEnv::builder();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => {
-                    env.write(serialize).unwrap();
-                }
-                InputItem::Bytes(items) => {
-                    env.write_frame(&items);
-                }
-            }
+    fn prove_with_limits(
+        &self,
+        inputs: &Input,
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, ProgramProvingReport), zkVMError> {
+        let opts = prover_opts(proof_kind)?;
+        if limits.max_duration.is_some() {
+            // The prover enforces the session limit itself, but not the duration.
+            self.execute_with_limits(inputs, limits)?;
         }
-        let env = env.build().map_err(|err| zkVMError::Other(err.into()))?;
+
+        let prover = default_prover();
+        let mut guest_output = GuestOutput::default();
+        let env = executor_env(inputs, limits, &mut guest_output)?;
 
         let now = std::time::Instant::now();
-        let prove_info = prover
-            .prove_with_opts(env, &self.program.elf, &ProverOpts::succinct())
-            .map_err(|err| zkVMError::Other(err.into()))?;
+        let prove_info = prover.prove_with_opts(env, &self.program.elf, &opts);
+        let prove_info = prove_info.map_err(|err| execution_error(err, guest_output))?;
         let proving_time = now.elapsed();
 
+        let public_values = prove_info.receipt.journal.bytes.clone();
         let encoded =
             borsh::to_vec(&prove_info.receipt).map_err(|err| zkVMError::Other(Box::new(err)))?;
-        Ok((encoded, ProgramProvingReport::new(proving_time)))
+        let envelope =
+            ProofEnvelope::new(NAME, SDK_VERSION, self.program_id(), proof_kind, encoded);
+        Ok((
+            public_values,
+            envelope.encode(),
+            ProgramProvingReport::new(proving_time),
+        ))
+    }
+
+    fn program_id(&self) -> ProgramDigest {
+        ProgramDigest::new(self.program.image_id.into())
+    }
+
+    fn input_encoding(&self) -> InputEncoding {
+        InputEncoding::Words
+    }
+}
+
+impl zkVMVerifier for EreRisc0 {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        verify_receipt(self.program.image_id, proof)
+    }
+
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Words
+    }
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+}
+
+/// Verifier-only handle for receipts of a single RISC Zero program, identified
+/// by its image ID.
+pub struct EreRisc0Verifier {
+    image_id: Digest,
+}
+
+impl EreRisc0Verifier {
+    pub fn from_image_id(image_id: Digest) -> Self {
+        Self { image_id }
     }
+}
 
-    fn verify(&self, proof: &[u8]) -> Result<(), zkVMError> {
-        let decoded: Receipt =
-            borsh::from_slice(&proof).map_err(|err| zkVMError::Other(Box::new(err)))?;
+impl zkVMVerifier for EreRisc0Verifier {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        verify_receipt(self.image_id, proof)
+    }
 
-        decoded
-            .verify(self.program.image_id)
-            .map_err(|err| zkVMError::Other(Box::new(err)))
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Words
     }
 
     fn name(&self) -> &'static str {
@@ -133,12 +229,218 @@ impl zkVM for EreRisc0 {
     }
 }
 
+/// Builds the executor environment from the inputs, bounding the session by
+/// `limits.max_cycles` and capturing what the guest writes to stdout and stderr
+/// into `guest_output`.
+fn executor_env<'a>(
+    inputs: &'a Input,
+    limits: &ExecutionLimits,
+    guest_output: &'a mut GuestOutput,
+) -> Result<ExecutorEnv<'a>, zkVMError> {
+    ExecutorEnv::builder()
+        .stdin(InputStream::new(inputs)?)
+        .session_limit(limits.max_cycles)
+        .stdout(&mut guest_output.stdout)
+        .stderr(&mut guest_output.stderr)
+        .build()
+        .map_err(|err| zkVMError::InvalidInput(err.into()))
+}
+
+/// The guest stdin, laid out as `ExecutorEnvBuilder::write`, `write_frame` and
+/// `write_slice` would, but reading [`InputItem::File`]s as the guest consumes
+/// them instead of copying them into the environment up front. The items are
+/// passed through `stdin` only, as any `write` would replace it.
+struct InputStream<'a> {
+    parts: VecDeque<Box<dyn Read + 'a>>,
+}
+
+impl<'a> InputStream<'a> {
+    fn new(inputs: &'a Input) -> Result<Self, zkVMError> {
+        let mut parts = VecDeque::<Box<dyn Read + 'a>>::new();
+        let frame = |len: u64| -> Result<Box<dyn Read + 'a>, InputError> {
+            let len = u32::try_from(len).map_err(|_| InputError::TooLarge(len))?;
+            Ok(Box::new(io::Cursor::new(len.to_le_bytes())))
+        };
+        for input in inputs.iter() {
+            match input {
+                InputItem::Object(serialize) => {
+                    let words = risc0_zkvm::serde::to_vec(serialize)
+                        .map_err(|err| zkVMError::InvalidInput(err.into()))?;
+                    let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+                    parts.push_back(Box::new(io::Cursor::new(bytes)));
+                }
+                InputItem::Bytes(items) => {
+                    parts.push_back(frame(items.len() as u64)?);
+                    parts.push_back(Box::new(items.as_slice()));
+                }
+                InputItem::File(path) => {
+                    let (file, len) = open_input_file(path)?;
+                    parts.push_back(frame(len)?);
+                    parts.push_back(Box::new(file));
+                }
+                InputItem::Encoded(obj) => {
+                    // `env::read` takes the words as they are, without a frame.
+                    let words = obj.encoded(InputEncoding::Words);
+                    parts.push_back(Box::new(words));
+                }
+            }
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl Read for InputStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while let Some(part) = self.parts.front_mut() {
+            match part.read(buf)? {
+                0 if !buf.is_empty() => {
+                    self.parts.pop_front();
+                }
+                read => return Ok(read),
+            }
+        }
+        Ok(0)
+    }
+}
+
+/// Prefix of the error risc0-zkvm returns when the guest panics.
+const GUEST_PANICKED_PREFIX: &str = "Guest panicked: ";
+/// Prefix of the error risc0-zkvm returns when the session limit is reached.
+const SESSION_LIMIT_PREFIX: &str = "Session limit exceeded";
+
+/// Maps a failed execution or proving, picking out guest panics and sessions
+/// running into the session limit.
+///
+/// risc0-zkvm (`^2.3.0`) returns both as plain `anyhow` errors without a typed
+/// variant to match on, so they are told apart by their message, and this is
+/// the only place that does. `test_execution_error_messages` fails if a risc0
+/// upgrade changes the messages.
+fn execution_error(err: anyhow::Error, guest_output: GuestOutput) -> zkVMError {
+    let message = err.to_string();
+    if message.starts_with(SESSION_LIMIT_PREFIX) {
+        return zkVMError::CycleLimitExceeded(err.into());
+    }
+    match message.strip_prefix(GUEST_PANICKED_PREFIX) {
+        Some(message) => zkVMError::GuestPanicked {
+            exit_code: None,
+            message: Some(message.to_string()),
+            output: guest_output,
+            source: err.into(),
+        },
+        None => zkVMError::Other(err.into()),
+    }
+}
+
+/// Maps the proof kind onto the receipt kind to prove. Groth16 wrapping requires
+/// Docker on x86, so it is gated behind the `snark` feature, and RISC Zero has
+/// no PLONK receipts.
+fn prover_opts(proof_kind: ProofKind) -> Result<ProverOpts, zkVMError> {
+    match proof_kind {
+        ProofKind::Core => Ok(ProverOpts::composite()),
+        ProofKind::Compressed => Ok(ProverOpts::succinct()),
+        #[cfg(feature = "snark")]
+        ProofKind::Groth16 => Ok(ProverOpts::groth16()),
+        #[cfg(not(feature = "snark"))]
+        ProofKind::Groth16 => Err(zkVMError::UnsupportedProofKind {
+            zkvm: NAME,
+            proof_kind,
+        }),
+        ProofKind::Plonk | ProofKind::Halo2 => Err(zkVMError::UnsupportedProofKind {
+            zkvm: NAME,
+            proof_kind,
+        }),
+    }
+}
+
+fn verify_receipt(image_id: Digest, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+    let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+    envelope.ensure_program(&ProgramDigest::new(image_id.into()))?;
+
+    let decoded: Receipt = borsh::from_slice(&envelope.payload)
+        .map_err(|err| zkVMError::ProofDecode(Box::new(err)))?;
+
+    decoded
+        .verify(image_id)
+        .map_err(|err| zkVMError::InvalidProof(Box::new(err)))?;
+
+    Ok(decoded.journal.bytes)
+}
+
+#[cfg(test)]
+mod input_tests {
+    use super::*;
+    use risc0_zkvm::serde::{Deserializer, Error, WordRead};
+    use serde::de::DeserializeOwned;
+    use zkvm_interface::test_utils::{GuestInputReader, check_input_parity};
+
+    /// The guest stdin, which `env::read` decodes with `risc0_zkvm::serde`
+    /// reading it a word at a time, and `env::read_frame` reads as a `u32`
+    /// length followed by that many bytes.
+    struct Risc0Guest {
+        stdin: Vec<u8>,
+        offset: usize,
+    }
+
+    impl Risc0Guest {
+        fn take(&mut self, len: usize) -> Result<&[u8], Error> {
+            let bytes = self
+                .stdin
+                .get(self.offset..self.offset + len)
+                .ok_or(Error::DeserializeUnexpectedEnd)?;
+            self.offset += len;
+            Ok(bytes)
+        }
+    }
+
+    impl WordRead for Risc0Guest {
+        fn read_words(&mut self, words: &mut [u32]) -> Result<(), Error> {
+            for word in words {
+                *word = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+            }
+            Ok(())
+        }
+
+        fn read_padded_bytes(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+            bytes.copy_from_slice(self.take(bytes.len())?);
+            self.take(bytes.len().next_multiple_of(4) - bytes.len())?;
+            Ok(())
+        }
+    }
+
+    impl GuestInputReader for Risc0Guest {
+        fn read<T: DeserializeOwned>(&mut self) -> T {
+            T::deserialize(&mut Deserializer::new(self)).unwrap()
+        }
+
+        fn read_bytes(&mut self) -> Vec<u8> {
+            let len = u32::from_le_bytes(self.take(4).unwrap().try_into().unwrap());
+            self.take(len as usize).unwrap().to_vec()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.offset == self.stdin.len()
+        }
+    }
+
+    #[test]
+    fn test_input_parity() {
+        check_input_parity(InputEncoding::Words, |input| {
+            let mut stdin = Vec::new();
+            InputStream::new(input)
+                .unwrap()
+                .read_to_end(&mut stdin)
+                .unwrap();
+            Risc0Guest { stdin, offset: 0 }
+        });
+    }
+}
+
 #[cfg(test)]
 mod prove_tests {
     use std::path::PathBuf;
 
     use super::*;
-    use zkvm_interface::Input;
+    use zkvm_interface::{Input, ProofEnvelopeError, test_utils::check_prove_verify};
 
     fn get_prove_test_guest_program_path() -> PathBuf {
         let workspace_dir = env!("CARGO_WORKSPACE_DIR");
@@ -168,38 +470,74 @@ mod prove_tests {
 
         let zkvm = EreRisc0::new(program, ProverResourceType::Cpu);
 
-        let proof_bytes = match zkvm.prove(&input_builder) {
-            Ok((prove_result, _)) => prove_result,
-            Err(err) => {
-                panic!("Proving error in test: {:?}", err);
-            }
-        };
+        let (public_values, _) = check_prove_verify(&zkvm, &input_builder, ProofKind::Compressed);
+
+        // The guest commits the first `u32` it reads to the journal.
+        assert_eq!(public_values, n.to_le_bytes());
+    }
+
+    #[test]
+    fn test_verifier_from_image_id_r0() {
+        let program = get_compiled_test_r0_elf_for_prove().unwrap();
+        let image_id = program.image_id;
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreRisc0::new(program, ProverResourceType::Cpu);
+        assert_eq!(zkvm.program_id(), ProgramDigest::new(image_id.into()));
+        let (public_values, proof_bytes, _) =
+            zkvm.prove(&input_builder, ProofKind::Compressed).unwrap();
+
+        let verifier = EreRisc0Verifier::from_image_id(image_id);
+        assert_eq!(verifier.verify(&proof_bytes).unwrap(), public_values);
+
+        let other_verifier = EreRisc0Verifier::from_image_id(Digest::ZERO);
+        assert!(matches!(
+            other_verifier.verify(&proof_bytes),
+            Err(zkVMError::ProofEnvelope(
+                ProofEnvelopeError::ProgramMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_prove_r0_core() {
+        let program = get_compiled_test_r0_elf_for_prove().unwrap();
 
-        assert!(!proof_bytes.is_empty(), "Proof bytes should not be empty.");
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreRisc0::new(program, ProverResourceType::Cpu);
+        let (public_values, proof_bytes, _) = zkvm.prove(&input_builder, ProofKind::Core).unwrap();
 
-        let verify_results = zkvm.verify(&proof_bytes).is_ok();
-        assert!(verify_results);
+        let envelope = ProofEnvelope::decode(&proof_bytes, NAME, SDK_VERSION).unwrap();
+        assert_eq!(envelope.proof_kind, ProofKind::Core);
+        assert_eq!(zkvm.verify(&proof_bytes).unwrap(), public_values);
 
-        // TODO: Check public inputs
+        assert!(matches!(
+            zkvm.prove(&input_builder, ProofKind::Plonk),
+            Err(zkVMError::UnsupportedProofKind { .. })
+        ));
     }
 
     #[test]
-    // TODO: Note: SP1 will panic here
-    // #[should_panic]
     fn test_prove_r0_fails_on_bad_input_causing_execution_failure() {
         let elf_bytes = get_compiled_test_r0_elf_for_prove().unwrap();
 
         let empty_input = Input::new();
 
         let zkvm = EreRisc0::new(elf_bytes, ProverResourceType::Cpu);
-        let prove_result = zkvm.prove(&empty_input);
-        assert!(prove_result.is_err());
+        let prove_result = zkvm.prove(&empty_input, ProofKind::Compressed);
+        assert!(matches!(prove_result, Err(zkVMError::GuestPanicked { .. })));
     }
 }
 
 #[cfg(test)]
 mod execute_tests {
-    use std::path::PathBuf;
+    use std::{path::PathBuf, time::Duration};
 
     use super::*;
     use zkvm_interface::Input;
@@ -232,11 +570,44 @@ mod execute_tests {
 
         let zkvm = EreRisc0::new(program, ProverResourceType::Cpu);
 
-        let result = zkvm.execute(&input_builder);
+        let (public_values, _) = match zkvm.execute(&input_builder) {
+            Ok(result) => result,
+            Err(e) => panic!("Execution error: {:?}", e),
+        };
 
-        if let Err(e) = &result {
-            panic!("Execution error: {:?}", e);
-        }
+        // The guest commits the first `u32` it reads to the journal.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<u32>().unwrap(), n);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_try_new_network_unsupported() {
+        let program = Risc0Program {
+            elf: Vec::new(),
+            image_id: Digest::ZERO,
+        };
+
+        assert!(matches!(
+            EreRisc0::try_new(program, ProverResourceType::Network(Default::default())),
+            Err(RiscZeroError::Resource(ResourceError::NetworkUnsupported))
+        ));
+    }
+
+    #[test]
+    fn test_execute_r0_limits() {
+        let program = get_compiled_test_r0_elf().unwrap();
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreRisc0::new(program, ProverResourceType::Cpu);
+        let limits = ExecutionLimits::new().with_max_duration(Duration::from_nanos(1));
+        assert!(matches!(
+            zkvm.execute_with_limits(&input_builder, &limits),
+            Err(zkVMError::Timeout(_))
+        ));
     }
 
     #[test]
@@ -249,8 +620,51 @@ mod execute_tests {
         let result = zkvm.execute(&empty_input);
 
         assert!(
-            result.is_err(),
+            matches!(
+                result,
+                Err(zkVMError::GuestPanicked {
+                    message: Some(_),
+                    ..
+                })
+            ),
             "execute should fail if guest expects input but none is provided."
         );
     }
+
+    #[test]
+    fn test_execution_error_messages() {
+        let program = get_compiled_test_r0_elf().unwrap();
+        let execute = |inputs: &Input, limits: &ExecutionLimits| {
+            let mut guest_output = GuestOutput::default();
+            let env = executor_env(inputs, limits, &mut guest_output).unwrap();
+            let session = ExecutorImpl::from_elf(env, &program.elf).and_then(|mut executor| {
+                executor.run_with_callback(|_| Ok(Box::new(NullSegmentRef)))
+            });
+            let Err(err) = session else {
+                panic!("execution should fail");
+            };
+            err
+        };
+
+        // The guest panics reading an input that is not there.
+        let err = execute(&Input::new(), &ExecutionLimits::default());
+        assert!(err.to_string().starts_with(GUEST_PANICKED_PREFIX), "{err}");
+        assert!(matches!(
+            execution_error(err, GuestOutput::default()),
+            zkVMError::GuestPanicked {
+                message: Some(_),
+                ..
+            }
+        ));
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+        let err = execute(&input_builder, &ExecutionLimits::new().with_max_cycles(1));
+        assert!(err.to_string().starts_with(SESSION_LIMIT_PREFIX), "{err}");
+        assert!(matches!(
+            execution_error(err, GuestOutput::default()),
+            zkVMError::CycleLimitExceeded(_)
+        ));
+    }
 }
@@ -27,19 +27,32 @@ pub fn compile(guest_program_full_path: &Path) -> Result<Vec<u8>, CompileError>
 
     info!("Compiling program: {}", guest_program_path_str);
 
-    let status = Command::new("docker")
+    let mut command = Command::new("docker");
+    command.args([
+        "run",
+        "--rm",
+        // Mount volumes, the guest at its own path so that relative path
+        // dependencies resolve as they do on the host
+        "-v",
+        &format!("{guest_program_path_str}:{guest_program_path_str}"),
+        "-v",
+        &format!("{elf_output_dir_str}:/output"),
+    ]);
+    // Mount `ere-guest` at its own path too, for guests depending on it by path.
+    let ere_guest_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../ere-guest");
+    if let Some(ere_guest_path) = ere_guest_path
+        .canonicalize()
+        .ok()
+        .and_then(|path| path.to_str().map(str::to_owned))
+    {
+        command.args(["-v", &format!("{ere_guest_path}:{ere_guest_path}:ro")]);
+    }
+    let status = command
         .args([
-            "run",
-            "--rm",
-            // Mount volumes
-            "-v",
-            &format!("{guest_program_path_str}:/guest-program"),
-            "-v",
-            &format!("{elf_output_dir_str}:/output"),
             tag,
             // Guest compiler execution
             "./guest-compiler",
-            "/guest-program",
+            guest_program_path_str,
             "/output",
         ])
         .status()
@@ -1,11 +1,38 @@
 use std::path::PathBuf;
 
 use thiserror::Error;
-use zkvm_interface::zkVMError;
+use zkvm_interface::{GuestOutput, zkVMError};
 
 impl From<SP1Error> for zkVMError {
     fn from(value: SP1Error) -> Self {
-        zkVMError::Other(Box::new(value))
+        if let SP1Error::Execute(ExecuteError::GuestPanicked {
+            exit_code, output, ..
+        }) = &value
+        {
+            return zkVMError::GuestPanicked {
+                exit_code: Some(*exit_code as i32),
+                message: output.panic_message(),
+                output: output.clone(),
+                source: Box::new(value),
+            };
+        }
+
+        match value {
+            err @ SP1Error::CompileError(_) => zkVMError::Compile(Box::new(err)),
+            err @ SP1Error::Execute(ExecuteError::CycleLimitExceeded(_)) => {
+                zkVMError::CycleLimitExceeded(Box::new(err))
+            }
+            err @ SP1Error::Client(ClientError::MissingNetworkPrivateKey) => {
+                zkVMError::ResourceUnavailable(Box::new(err))
+            }
+            err @ SP1Error::Verify(VerifyError::Bincode(_)) => {
+                zkVMError::ProofDecode(Box::new(err))
+            }
+            err @ SP1Error::Verify(VerifyError::Client(_)) => {
+                zkVMError::InvalidProof(Box::new(err))
+            }
+            err => zkVMError::Other(Box::new(err)),
+        }
     }
 }
 
@@ -14,6 +41,9 @@ pub enum SP1Error {
     #[error(transparent)]
     CompileError(#[from] CompileError),
 
+    #[error(transparent)]
+    Client(#[from] ClientError),
+
     #[error(transparent)]
     Execute(#[from] ExecuteError),
 
@@ -22,6 +52,9 @@ pub enum SP1Error {
 
     #[error(transparent)]
     Verify(#[from] VerifyError),
+
+    #[error(transparent)]
+    Keys(#[from] KeysError),
 }
 
 /// Errors that can be encountered while compiling a SP1 program
@@ -43,10 +76,29 @@ pub enum CompileError {
     ReadCompiledELFProgram(#[source] std::io::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error(
+        "Network proving requires a private key. Set NETWORK_PRIVATE_KEY environment variable or provide api_key in NetworkProverConfig"
+    )]
+    MissingNetworkPrivateKey,
+}
+
 #[derive(Debug, Error)]
 pub enum ExecuteError {
     #[error("SP1 execution failed: {0}")]
     Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("SP1 guest exited with non-zero exit code {exit_code}: {source}")]
+    GuestPanicked {
+        exit_code: u32,
+        output: GuestOutput,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
+    #[error("SP1 execution exceeded the limit of {0} cycles")]
+    CycleLimitExceeded(u64),
 }
 
 #[derive(Debug, Error)]
@@ -63,6 +115,34 @@ pub enum VerifyError {
     #[error("Deserialising proof failed: {0}")]
     Bincode(#[from] bincode::Error),
 
+    #[error("Deserialising verifying key failed: {0}")]
+    VerifyingKey(#[source] bincode::Error),
+
     #[error("SP1 SDK verification failed: {0}")]
     Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
+
+#[derive(Debug, Error)]
+pub enum KeysError {
+    #[error("Failed to access key file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Serialising keys with `bincode` failed: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    #[error("Keys in {0} were generated for a different program")]
+    ProgramMismatch(PathBuf),
+}
+
+impl KeysError {
+    pub(crate) fn io(path: &std::path::Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
@@ -1,15 +1,18 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
-use std::time::Instant;
+use std::{fs, path::Path, sync::OnceLock, time::Instant};
 
+use sp1_core_executor::ExecutionError;
 use sp1_sdk::{
-    CpuProver, CudaProver, NetworkProver, Prover, ProverClient, SP1ProofWithPublicValues,
-    SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+    CpuProver, CudaProver, HashableKey, NetworkProver, Prover, ProverClient, SP1ProofMode,
+    SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
 };
 use tracing::info;
 use zkvm_interface::{
-    Compiler, Input, InputItem, NetworkProverConfig, ProgramExecutionReport, ProgramProvingReport,
-    ProverResourceType, zkVM, zkVMError,
+    Compiler, ExecutionLimits, GuestOutput, Input, InputEncoding, InputError, InputItem,
+    NetworkProverConfig, ProgramDigest, ProgramExecutionReport, ProgramProvingReport,
+    ProofEnvelope, ProofKind, ProverResourceType, PublicValues, PublicValuesEncoding,
+    read_input_file, zkVM, zkVMError, zkVMVerifier,
 };
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
@@ -17,7 +20,14 @@ include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
 mod compile;
 
 mod error;
-use error::{ExecuteError, ProveError, SP1Error, VerifyError};
+use error::{ClientError, ExecuteError, KeysError, ProveError, SP1Error, VerifyError};
+
+/// File name of the program id (the verifying key hash) written by [`EreSP1::export_keys`].
+const PROGRAM_DIGEST_FILE: &str = "program_digest";
+/// File name of the `bincode`-encoded proving key.
+const PROVING_KEY_FILE: &str = "sp1_pk.bin";
+/// File name of the `bincode`-encoded verifying key.
+const VERIFYING_KEY_FILE: &str = "sp1_vk.bin";
 
 enum ProverType {
     Cpu(CpuProver),
@@ -37,34 +47,44 @@ impl ProverType {
         }
     }
 
+    /// Executes the program, capturing what the guest writes to stdout and
+    /// stderr into `guest_output`.
     fn execute(
         &self,
         program: &<RV32_IM_SUCCINCT_ZKVM_ELF as Compiler>::Program,
         input: &SP1Stdin,
+        max_cycles: Option<u64>,
+        guest_output: &mut GuestOutput,
     ) -> Result<(sp1_sdk::SP1PublicValues, sp1_sdk::ExecutionReport), SP1Error> {
-        let cpu_executor_builder = match self {
+        let mut cpu_executor_builder = match self {
             ProverType::Cpu(cpu_prover) => cpu_prover.execute(program, input),
             ProverType::Gpu(cuda_prover) => cuda_prover.execute(program, input),
             ProverType::Network(network_prover) => network_prover.execute(program, input),
-        };
+        }
+        .stdout(&mut guest_output.stdout)
+        .stderr(&mut guest_output.stderr);
+        if let Some(max_cycles) = max_cycles {
+            cpu_executor_builder = cpu_executor_builder.max_cycles(max_cycles);
+        }
 
-        cpu_executor_builder
-            .run()
-            .map_err(|e| SP1Error::Execute(ExecuteError::Client(e.into())))
+        let result = cpu_executor_builder.run();
+        result.map_err(|e| execute_error(e, guest_output.clone()).into())
     }
     fn prove(
         &self,
         pk: &SP1ProvingKey,
         input: &SP1Stdin,
+        mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues, SP1Error> {
         match self {
-            ProverType::Cpu(cpu_prover) => cpu_prover.prove(pk, input).compressed().run(),
-            ProverType::Gpu(cuda_prover) => cuda_prover.prove(pk, input).compressed().run(),
-            ProverType::Network(network_prover) => {
-                network_prover.prove(pk, input).compressed().run()
-            }
+            ProverType::Cpu(cpu_prover) => cpu_prover.prove(pk, input).mode(mode).run(),
+            ProverType::Gpu(cuda_prover) => cuda_prover.prove(pk, input).mode(mode).run(),
+            ProverType::Network(network_prover) => network_prover.prove(pk, input).mode(mode).run(),
         }
-        .map_err(|e| SP1Error::Prove(ProveError::Client(e.into())))
+        .map_err(|e| match exit_code(&e) {
+            Some(_) => execute_error(e, GuestOutput::default()).into(),
+            None => SP1Error::Prove(ProveError::Client(e.into())),
+        })
     }
 
     fn verify(
@@ -81,6 +101,29 @@ impl ProverType {
     }
 }
 
+/// Returns the exit code of a guest that panicked, which the executor reports
+/// as a halt with a non-zero exit code.
+fn exit_code(err: &anyhow::Error) -> Option<u32> {
+    match err.downcast_ref::<ExecutionError>() {
+        Some(ExecutionError::HaltWithNonZeroExitCode(exit_code)) => Some(*exit_code),
+        _ => None,
+    }
+}
+
+fn execute_error(err: anyhow::Error, output: GuestOutput) -> ExecuteError {
+    if let Some(ExecutionError::ExceededCycleLimit(max_cycles)) = err.downcast_ref() {
+        return ExecuteError::CycleLimitExceeded(*max_cycles);
+    }
+    match exit_code(&err) {
+        Some(exit_code) => ExecuteError::GuestPanicked {
+            exit_code,
+            output,
+            source: err.into(),
+        },
+        None => ExecuteError::Client(err.into()),
+    }
+}
+
 #[allow(non_camel_case_types)]
 pub struct RV32_IM_SUCCINCT_ZKVM_ELF;
 pub struct EreSP1 {
@@ -112,7 +155,7 @@ impl Compiler for RV32_IM_SUCCINCT_ZKVM_ELF {
 }
 
 impl EreSP1 {
-    fn create_network_prover(config: &NetworkProverConfig) -> NetworkProver {
+    fn create_network_prover(config: &NetworkProverConfig) -> Result<NetworkProver, SP1Error> {
         let mut builder = ProverClient::builder().network();
         // Check if we have a private key in the config or environment
         if let Some(api_key) = &config.api_key {
@@ -120,9 +163,7 @@ impl EreSP1 {
         } else if let Ok(private_key) = std::env::var("NETWORK_PRIVATE_KEY") {
             builder = builder.private_key(&private_key);
         } else {
-            panic!(
-                "Network proving requires a private key. Set NETWORK_PRIVATE_KEY environment variable or provide api_key in NetworkProverConfig"
-            );
+            return Err(ClientError::MissingNetworkPrivateKey.into());
         }
         // Set the RPC URL if provided
         if !config.endpoint.is_empty() {
@@ -131,87 +172,207 @@ impl EreSP1 {
             builder = builder.rpc_url(&rpc_url);
         }
         // Otherwise SP1 SDK will use its default RPC URL
-        builder.build()
+        Ok(builder.build())
     }
 
-    fn create_client(resource: &ProverResourceType) -> ProverType {
-        match resource {
+    fn create_client(resource: &ProverResourceType) -> Result<ProverType, SP1Error> {
+        Ok(match resource {
             ProverResourceType::Cpu => ProverType::Cpu(ProverClient::builder().cpu().build()),
             ProverResourceType::Gpu => ProverType::Gpu(ProverClient::builder().cuda().build()),
             ProverResourceType::Network(config) => {
-                ProverType::Network(Self::create_network_prover(config))
+                ProverType::Network(Self::create_network_prover(config)?)
             }
-        }
+        })
     }
 
+    /// Runs the setup of the program once, so the keys are reused by every
+    /// later call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client cannot be created, see [`EreSP1::try_new`].
     pub fn new(
         program: <RV32_IM_SUCCINCT_ZKVM_ELF as Compiler>::Program,
         resource: ProverResourceType,
     ) -> Self {
-        let (pk, vk) = Self::create_client(&resource).setup(&program);
+        Self::try_new(program, resource).expect("Failed to create SP1 client")
+    }
 
-        Self {
+    /// Like [`EreSP1::new`], but returns an error instead of panicking, e.g. if
+    /// network proving is selected without a private key.
+    pub fn try_new(
+        program: <RV32_IM_SUCCINCT_ZKVM_ELF as Compiler>::Program,
+        resource: ProverResourceType,
+    ) -> Result<Self, SP1Error> {
+        let (pk, vk) = Self::create_client(&resource)?.setup(&program);
+
+        Ok(Self {
             program,
             pk,
             vk,
             resource,
+        })
+    }
+
+    /// Returns the `bincode`-encoded verifying key, from which an
+    /// [`EreSP1Verifier`] can be created.
+    pub fn vk_bytes(&self) -> Result<Vec<u8>, SP1Error> {
+        Ok(bincode::serialize(&self.vk).map_err(KeysError::from)?)
+    }
+
+    /// Writes the proving and verifying keys to `dir`, alongside the [`zkVM::program_id`] they
+    /// were generated for, so that [`EreSP1::load_keys`] can skip the setup next time.
+    pub fn export_keys(&self, dir: &Path) -> Result<(), SP1Error> {
+        fs::create_dir_all(dir).map_err(|source| KeysError::io(dir, source))?;
+        write_key_file(&dir.join(PROGRAM_DIGEST_FILE), self.program_id().as_bytes())?;
+        let pk = bincode::serialize(&self.pk).map_err(KeysError::from)?;
+        write_key_file(&dir.join(PROVING_KEY_FILE), &pk)?;
+        let vk = bincode::serialize(&self.vk).map_err(KeysError::from)?;
+        write_key_file(&dir.join(VERIFYING_KEY_FILE), &vk)?;
+        Ok(())
+    }
+
+    /// Creates an instance from keys previously written by [`EreSP1::export_keys`] instead of
+    /// running the setup.
+    ///
+    /// Keys that were generated for a different program are rejected: the proving key has to be
+    /// of `program`, and both it and the verifying key have to hash to the exported program id.
+    pub fn load_keys(
+        program: <RV32_IM_SUCCINCT_ZKVM_ELF as Compiler>::Program,
+        resource: ProverResourceType,
+        dir: &Path,
+    ) -> Result<Self, SP1Error> {
+        let digest = read_key_file(&dir.join(PROGRAM_DIGEST_FILE))?;
+        let pk: SP1ProvingKey = bincode::deserialize(&read_key_file(&dir.join(PROVING_KEY_FILE))?)
+            .map_err(KeysError::from)?;
+        let vk: SP1VerifyingKey =
+            bincode::deserialize(&read_key_file(&dir.join(VERIFYING_KEY_FILE))?)
+                .map_err(KeysError::from)?;
+
+        if pk.elf[..] != program[..]
+            || pk.vk.bytes32_raw() != vk.bytes32_raw()
+            || digest != vk.bytes32_raw()
+        {
+            return Err(KeysError::ProgramMismatch(dir.to_path_buf()).into());
         }
+
+        Ok(Self {
+            program,
+            pk,
+            vk,
+            resource,
+        })
     }
 }
 
-impl zkVM for EreSP1 {
-    fn execute(&self, inputs: &Input) -> Result<zkvm_interface::ProgramExecutionReport, zkVMError> {
-        let mut stdin = SP1Stdin::new();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => stdin.write(serialize),
-                InputItem::Bytes(items) => stdin.write_slice(items),
-            }
+fn write_key_file(path: &Path, contents: &[u8]) -> Result<(), KeysError> {
+    fs::write(path, contents).map_err(|source| KeysError::io(path, source))
+}
+
+fn read_key_file(path: &Path) -> Result<Vec<u8>, KeysError> {
+    fs::read(path).map_err(|source| KeysError::io(path, source))
+}
+
+fn stdin_from_inputs(inputs: &Input) -> Result<SP1Stdin, InputError> {
+    let mut stdin = SP1Stdin::new();
+    for input in inputs.iter() {
+        match input {
+            InputItem::Object(serialize) => stdin.write(serialize),
+            InputItem::Bytes(items) => stdin.write_slice(items),
+            InputItem::File(path) => stdin.write_vec(read_input_file(path)?),
+            InputItem::Encoded(obj) => stdin.write_slice(obj.encoded(InputEncoding::Bincode)),
         }
+    }
+    Ok(stdin)
+}
 
-        let client = Self::create_client(&self.resource);
+impl zkVM for EreSP1 {
+    fn execute_with_limits(
+        &self,
+        inputs: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, zkvm_interface::ProgramExecutionReport), zkVMError> {
+        let stdin = stdin_from_inputs(inputs)?;
+
+        let program = self.program.clone();
+        let resource = self.resource.clone();
+        let max_cycles = limits.max_cycles;
         let start = Instant::now();
-        let (_, exec_report) = client.execute(&self.program, &stdin)?;
-        Ok(ProgramExecutionReport {
+        // The executor stops at `max_cycles` itself, but the SDK has no way to
+        // stop it once `max_duration` has passed. On timeout the worker thread
+        // is detached and keeps executing until the guest halts or runs into
+        // `max_cycles`, so pass `max_cycles` too to bound it.
+        let (public_values, exec_report, guest_output) =
+            limits.run_with_deadline(move || {
+                let mut guest_output = GuestOutput::default();
+                let (public_values, exec_report) = Self::create_client(&resource)?.execute(
+                    &program,
+                    &stdin,
+                    max_cycles,
+                    &mut guest_output,
+                )?;
+                Ok::<_, SP1Error>((public_values, exec_report, guest_output))
+            })??;
+        let report = ProgramExecutionReport {
             total_num_cycles: exec_report.total_instruction_count(),
             region_cycles: exec_report.cycle_tracker.into_iter().collect(),
             execution_duration: start.elapsed(),
-        })
+            guest_output,
+        };
+
+        Ok((public_values.to_vec(), report))
     }
 
-    fn prove(
+    fn prove_with_limits(
         &self,
         inputs: &zkvm_interface::Input,
-    ) -> Result<(Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, zkvm_interface::ProgramProvingReport), zkVMError> {
         info!("Generating proof…");
 
-        let mut stdin = SP1Stdin::new();
-        for input in inputs.iter() {
-            match input {
-                InputItem::Object(serialize) => stdin.write(serialize),
-                InputItem::Bytes(items) => stdin.write_slice(items),
-            };
-        }
+        let mode = proof_mode(proof_kind)?;
+        // The prover has no limits of its own, and fails on a panicking guest
+        // without its exit code, so execute the program before proving it.
+        self.execute_with_limits(inputs, limits)?;
+
+        let stdin = stdin_from_inputs(inputs)?;
 
-        let client = Self::create_client(&self.resource);
+        let client = Self::create_client(&self.resource)?;
         let start = std::time::Instant::now();
-        let proof_with_inputs = client.prove(&self.pk, &stdin)?;
+        let proof_with_inputs = client.prove(&self.pk, &stdin, mode)?;
         let proving_time = start.elapsed();
 
-        let bytes = bincode::serialize(&proof_with_inputs)
+        let public_values = proof_with_inputs.public_values.to_vec();
+        let payload = bincode::serialize(&proof_with_inputs)
             .map_err(|err| SP1Error::Prove(ProveError::Bincode(err)))?;
+        let envelope =
+            ProofEnvelope::new(NAME, SDK_VERSION, self.program_id(), proof_kind, payload);
+
+        Ok((
+            public_values,
+            envelope.encode(),
+            ProgramProvingReport::new(proving_time),
+        ))
+    }
 
-        Ok((bytes, ProgramProvingReport::new(proving_time)))
+    fn program_id(&self) -> ProgramDigest {
+        ProgramDigest::new(self.vk.bytes32_raw())
     }
 
-    fn verify(&self, proof: &[u8]) -> Result<(), zkVMError> {
-        info!("Verifying proof…");
+    fn input_encoding(&self) -> InputEncoding {
+        InputEncoding::Bincode
+    }
+}
 
-        let proof: SP1ProofWithPublicValues = bincode::deserialize(proof)
-            .map_err(|err| SP1Error::Verify(VerifyError::Bincode(err)))?;
+impl zkVMVerifier for EreSP1 {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        let client = Self::create_client(&self.resource)?;
+        verify_proof(&client, &self.vk, proof)
+    }
 
-        let client = Self::create_client(&self.resource);
-        client.verify(&proof, &self.vk).map_err(zkVMError::from)
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Bincode
     }
 
     fn name(&self) -> &'static str {
@@ -223,9 +384,125 @@ impl zkVM for EreSP1 {
     }
 }
 
+/// Verifier-only handle for proofs of a single SP1 program, built from its
+/// verifying key alone.
+pub struct EreSP1Verifier {
+    vk: SP1VerifyingKey,
+    /// CPU prover used for verification, built on the first `verify` so that
+    /// constructing the verifier stays cheap.
+    client: OnceLock<ProverType>,
+}
+
+impl EreSP1Verifier {
+    /// Creates a verifier from a `bincode`-encoded verifying key, as returned by
+    /// [`EreSP1::vk_bytes`].
+    pub fn from_vk(vk: &[u8]) -> Result<Self, SP1Error> {
+        let vk = bincode::deserialize(vk).map_err(VerifyError::VerifyingKey)?;
+        Ok(Self {
+            vk,
+            client: OnceLock::new(),
+        })
+    }
+}
+
+impl zkVMVerifier for EreSP1Verifier {
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+        // Verification only needs the CPU prover.
+        let client = self
+            .client
+            .get_or_init(|| ProverType::Cpu(ProverClient::builder().cpu().build()));
+        verify_proof(client, &self.vk, proof)
+    }
+
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::Bincode
+    }
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+}
+
+/// Maps the proof kind onto the SP1 proof mode. Wrapping into a SNARK requires
+/// Docker unless SP1 is built with native gnark, so it is gated behind the
+/// `snark` feature.
+fn proof_mode(proof_kind: ProofKind) -> Result<SP1ProofMode, zkVMError> {
+    match proof_kind {
+        ProofKind::Core => Ok(SP1ProofMode::Core),
+        ProofKind::Compressed => Ok(SP1ProofMode::Compressed),
+        #[cfg(feature = "snark")]
+        ProofKind::Groth16 => Ok(SP1ProofMode::Groth16),
+        #[cfg(feature = "snark")]
+        ProofKind::Plonk => Ok(SP1ProofMode::Plonk),
+        #[cfg(not(feature = "snark"))]
+        ProofKind::Groth16 | ProofKind::Plonk => Err(zkVMError::UnsupportedProofKind {
+            zkvm: NAME,
+            proof_kind,
+        }),
+        ProofKind::Halo2 => Err(zkVMError::UnsupportedProofKind {
+            zkvm: NAME,
+            proof_kind,
+        }),
+    }
+}
+
+fn verify_proof(
+    client: &ProverType,
+    vk: &SP1VerifyingKey,
+    proof: &[u8],
+) -> Result<PublicValues, zkVMError> {
+    info!("Verifying proof…");
+
+    let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+    envelope.ensure_program(&ProgramDigest::new(vk.bytes32_raw()))?;
+
+    let proof: SP1ProofWithPublicValues = bincode::deserialize(&envelope.payload)
+        .map_err(|err| SP1Error::Verify(VerifyError::Bincode(err)))?;
+
+    client.verify(&proof, vk)?;
+
+    Ok(proof.public_values.to_vec())
+}
+
+#[cfg(test)]
+mod input_tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use zkvm_interface::test_utils::{GuestInputReader, check_input_parity};
+
+    /// `sp1_zkvm::io::read_vec` takes the next frame of `SP1Stdin`, and
+    /// `sp1_zkvm::io::read` decodes it with `bincode`.
+    struct Sp1Guest(std::vec::IntoIter<Vec<u8>>);
+
+    impl GuestInputReader for Sp1Guest {
+        fn read<T: DeserializeOwned>(&mut self) -> T {
+            bincode::deserialize(&self.read_bytes()).unwrap()
+        }
+
+        fn read_bytes(&mut self) -> Vec<u8> {
+            self.0.next().expect("no frame left")
+        }
+
+        fn is_empty(&self) -> bool {
+            self.0.len() == 0
+        }
+    }
+
+    #[test]
+    fn test_input_parity() {
+        check_input_parity(InputEncoding::Bincode, |input| {
+            Sp1Guest(stdin_from_inputs(input).unwrap().buffer.into_iter())
+        });
+    }
+}
+
 #[cfg(test)]
 mod execute_tests {
-    use std::path::PathBuf;
+    use std::{path::PathBuf, time::Duration};
 
     use super::*;
     use zkvm_interface::Input;
@@ -259,11 +536,20 @@ mod execute_tests {
 
         let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
 
-        let result = zkvm.execute(&input_builder);
+        let (public_values, report) = match zkvm.execute(&input_builder) {
+            Ok(result) => result,
+            Err(e) => panic!("Execution error: {:?}", e),
+        };
 
-        if let Err(e) = &result {
-            panic!("Execution error: {:?}", e);
-        }
+        // The guest commits `(n + a) * 2` as a `u32`.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<u32>().unwrap(), (n + a as u32) * 2);
+        assert!(reader.is_empty());
+
+        // It computes it in the `compute` cycle-tracking region.
+        assert!(report.region_cycles["compute"] < report.total_num_cycles);
+
+        assert_eq!(report.guest_output.stdout, b"n = 42, a = 42\n");
     }
 
     #[test]
@@ -276,10 +562,48 @@ mod execute_tests {
         let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
         let result = zkvm.execute(&empty_input);
 
-        assert!(
-            result.is_err(),
-            "execute should fail if guest expects input but none is provided."
-        );
+        match result {
+            Err(zkVMError::GuestPanicked {
+                exit_code: Some(_),
+                message: Some(message),
+                output,
+                ..
+            }) => {
+                assert_eq!(output.panic_message(), Some(message));
+                assert!(output.stdout.is_empty());
+            }
+            other => panic!(
+                "execute should fail if guest expects input but none is provided, got {other:?}"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_execute_sp1_cycle_limit() {
+        let elf_bytes = get_compiled_test_sp1_elf()
+            .expect("Failed to compile test SP1 guest for execution test");
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
+        let (_, report) = zkvm.execute(&input_builder).unwrap();
+
+        let limits = ExecutionLimits::new().with_max_cycles(report.total_num_cycles);
+        zkvm.execute_with_limits(&input_builder, &limits)
+            .expect("execution should fit its own cycle count");
+
+        let limits = ExecutionLimits::new().with_max_cycles(report.total_num_cycles / 2);
+        assert!(matches!(
+            zkvm.execute_with_limits(&input_builder, &limits),
+            Err(zkVMError::CycleLimitExceeded(_))
+        ));
+        let limits = ExecutionLimits::new().with_max_duration(Duration::from_nanos(1));
+        assert!(matches!(
+            zkvm.execute_with_limits(&input_builder, &limits),
+            Err(zkVMError::Timeout(_))
+        ));
     }
 }
 
@@ -288,7 +612,7 @@ mod prove_tests {
     use std::path::PathBuf;
 
     use super::*;
-    use zkvm_interface::Input;
+    use zkvm_interface::{Input, ProofEnvelopeError, test_utils::check_prove_verify};
 
     fn get_prove_test_guest_program_path() -> PathBuf {
         let workspace_dir = env!("CARGO_WORKSPACE_DIR");
@@ -319,23 +643,117 @@ mod prove_tests {
 
         let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
 
-        let proof_bytes = match zkvm.prove(&input_builder) {
-            Ok((prove_result, _)) => prove_result,
-            Err(err) => {
-                panic!("Proving error in test: {:?}", err);
-            }
-        };
+        let (public_values, proof_bytes) =
+            check_prove_verify(&zkvm, &input_builder, ProofKind::Compressed);
 
-        assert!(!proof_bytes.is_empty(), "Proof bytes should not be empty.");
+        // The guest commits `(n + a) * 2` as a `u32`.
+        assert_eq!(public_values, ((n + a as u32) * 2).to_le_bytes());
+
+        let mut foreign_envelope = ProofEnvelope::decode(&proof_bytes, NAME, SDK_VERSION).unwrap();
+        foreign_envelope.zkvm_name = "zisk".to_string();
+        assert!(matches!(
+            zkvm.verify(&foreign_envelope.encode()),
+            Err(zkVMError::ProofEnvelope(
+                ProofEnvelopeError::ZkvmMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_export_and_load_keys_sp1() {
+        let elf_bytes = get_compiled_test_sp1_elf_for_prove()
+            .expect("Failed to compile test SP1 guest for proving test");
+        let keys_dir = tempfile::tempdir().unwrap();
+
+        let zkvm = EreSP1::new(elf_bytes.clone(), ProverResourceType::Cpu);
+        zkvm.export_keys(keys_dir.path()).unwrap();
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let loaded = EreSP1::load_keys(elf_bytes.clone(), ProverResourceType::Cpu, keys_dir.path())
+            .expect("Keys should load for the program they were generated for");
+        assert_eq!(loaded.program_id(), zkvm.program_id());
+        let (public_values, proof_bytes, _) =
+            loaded.prove(&input_builder, ProofKind::Compressed).unwrap();
+        assert_eq!(zkvm.verify(&proof_bytes).unwrap(), public_values);
+
+        let mut other_program = elf_bytes.clone();
+        other_program.push(0);
+        assert!(matches!(
+            EreSP1::load_keys(other_program, ProverResourceType::Cpu, keys_dir.path()),
+            Err(SP1Error::Keys(KeysError::ProgramMismatch(_)))
+        ));
+
+        // A verifying key of another program is rejected too.
+        let other = EreSP1::new(
+            get_compiled_test_sp1_elf().expect("Failed to compile test SP1 guest"),
+            ProverResourceType::Cpu,
+        );
+        fs::write(
+            keys_dir.path().join(VERIFYING_KEY_FILE),
+            other.vk_bytes().unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            EreSP1::load_keys(elf_bytes, ProverResourceType::Cpu, keys_dir.path()),
+            Err(SP1Error::Keys(KeysError::ProgramMismatch(_)))
+        ));
+    }
+
+    #[test]
+    fn test_verifier_from_vk_sp1() {
+        let elf_bytes = get_compiled_test_sp1_elf_for_prove()
+            .expect("Failed to compile test SP1 guest for proving test");
 
-        let verify_results = zkvm.verify(&proof_bytes).is_ok();
-        assert!(verify_results);
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
+        let (public_values, proof_bytes, _) =
+            zkvm.prove(&input_builder, ProofKind::Compressed).unwrap();
 
-        // TODO: Check public inputs
+        let verifier = EreSP1Verifier::from_vk(&zkvm.vk_bytes().unwrap()).unwrap();
+        assert_eq!(verifier.verify(&proof_bytes).unwrap(), public_values);
+
+        assert!(EreSP1Verifier::from_vk(&[]).is_err());
+    }
+
+    #[test]
+    fn test_prove_sp1_core() {
+        let elf_bytes = get_compiled_test_sp1_elf_for_prove()
+            .expect("Failed to compile test SP1 guest for proving test");
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
+        let (public_values, proof_bytes, _) = zkvm.prove(&input_builder, ProofKind::Core).unwrap();
+
+        let envelope = ProofEnvelope::decode(&proof_bytes, NAME, SDK_VERSION).unwrap();
+        assert_eq!(envelope.proof_kind, ProofKind::Core);
+        assert_eq!(zkvm.verify(&proof_bytes).unwrap(), public_values);
+    }
+
+    #[test]
+    #[cfg(not(feature = "snark"))]
+    fn test_prove_sp1_snark_requires_feature() {
+        let elf_bytes = get_compiled_test_sp1_elf_for_prove()
+            .expect("Failed to compile test SP1 guest for proving test");
+
+        let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
+        for proof_kind in [ProofKind::Groth16, ProofKind::Plonk] {
+            assert!(matches!(
+                zkvm.prove(&Input::new(), proof_kind),
+                Err(zkVMError::UnsupportedProofKind { .. })
+            ));
+        }
     }
 
     #[test]
-    #[should_panic]
     fn test_prove_sp1_fails_on_bad_input_causing_execution_failure() {
         let elf_bytes = get_compiled_test_sp1_elf_for_prove()
             .expect("Failed to compile test SP1 guest for proving test");
@@ -343,8 +761,14 @@ mod prove_tests {
         let empty_input = Input::new();
 
         let zkvm = EreSP1::new(elf_bytes, ProverResourceType::Cpu);
-        let prove_result = zkvm.prove(&empty_input);
-        assert!(prove_result.is_err())
+        let prove_result = zkvm.prove(&empty_input, ProofKind::Compressed);
+        assert!(matches!(
+            prove_result,
+            Err(zkVMError::GuestPanicked {
+                exit_code: Some(_),
+                ..
+            })
+        ));
     }
 
     #[test]
@@ -378,8 +802,8 @@ mod prove_tests {
         assert!(exec_result.is_ok(), "Execution should succeed");
 
         // Now prove using the network
-        let proof_bytes = match zkvm.prove(&input_builder) {
-            Ok((prove_result, report)) => {
+        let proof_bytes = match zkvm.prove(&input_builder, ProofKind::Compressed) {
+            Ok((_, prove_result, report)) => {
                 println!("Network proving completed in {:?}", report.proving_time);
                 prove_result
             }
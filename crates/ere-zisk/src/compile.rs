@@ -94,12 +94,12 @@ pub fn compile_zisk_program(program_crate_path: &Path) -> Result<Vec<u8>, Compil
             .arg("--message-format=plain")
             .output()
             .map_err(|e| CompileError::CargoLocateProject { source: e })?;
-        PathBuf::from(
-            String::from_utf8_lossy(&output.stdout)
-                .trim()
-                .strip_suffix("Cargo.toml")
-                .expect("location to be path to Cargo.toml"),
-        )
+        let location = String::from_utf8_lossy(&output.stdout);
+        let workspace_path = location
+            .trim()
+            .strip_suffix("Cargo.toml")
+            .ok_or_else(|| CompileError::UnexpectedProjectLocation(location.trim().to_string()))?;
+        PathBuf::from(workspace_path)
     };
 
     let elf_path = program_workspace_path
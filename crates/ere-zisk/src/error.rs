@@ -1,10 +1,47 @@
-use std::{io, path::PathBuf, process::ExitStatus};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+};
 use thiserror::Error;
-use zkvm_interface::zkVMError;
+use zkvm_interface::{GuestOutput, zkVMError};
 
 impl From<ZiskError> for zkVMError {
     fn from(value: ZiskError) -> Self {
-        zkVMError::Other(Box::new(value))
+        if let ZiskError::Execute(ExecuteError::ZiskemuFailed { status, output }) = &value {
+            return zkVMError::GuestPanicked {
+                exit_code: status.code(),
+                message: output.panic_message(),
+                output: output.clone(),
+                source: Box::new(value),
+            };
+        }
+
+        match value {
+            err @ ZiskError::Compile(_) => zkVMError::Compile(Box::new(err)),
+            err @ ZiskError::Resource(_) => zkVMError::Unsupported(Box::new(err)),
+            err @ (ZiskError::Execute(ExecuteError::SerializeInput(_))
+            | ZiskError::Prove(ProveError::SerializeInput(_))) => {
+                zkVMError::InvalidInput(Box::new(err))
+            }
+            err @ (ZiskError::Execute(ExecuteError::Ziskemu { .. })
+            | ZiskError::Prove(
+                ProveError::CargoZiskRomSetup { .. } | ProveError::CargoZiskProve { .. },
+            )
+            | ZiskError::Verify(VerifyError::CargoZiskVerify { .. })) => {
+                zkVMError::ResourceUnavailable(Box::new(err))
+            }
+            err @ ZiskError::Verify(VerifyError::Bincode(_) | VerifyError::PublicValues(_)) => {
+                zkVMError::ProofDecode(Box::new(err))
+            }
+            err @ ZiskError::Verify(VerifyError::InvalidProof(_)) => {
+                zkVMError::InvalidProof(Box::new(err))
+            }
+            err @ ZiskError::Verify(VerifyError::ProgramNotBound) => {
+                zkVMError::Unsupported(Box::new(err))
+            }
+            err => zkVMError::Other(Box::new(err)),
+        }
     }
 }
 
@@ -13,6 +50,9 @@ pub enum ZiskError {
     #[error(transparent)]
     Compile(#[from] CompileError),
 
+    #[error(transparent)]
+    Resource(#[from] ResourceError),
+
     #[error(transparent)]
     Execute(#[from] ExecuteError),
 
@@ -21,6 +61,9 @@ pub enum ZiskError {
 
     #[error(transparent)]
     Verify(#[from] VerifyError),
+
+    #[error(transparent)]
+    Keys(#[from] KeysError),
 }
 
 #[derive(Debug, Error)]
@@ -66,6 +109,8 @@ pub enum CompileError {
         #[source]
         source: io::Error,
     },
+    #[error("`cargo locate-project` returned {0:?}, expected a path to Cargo.toml")]
+    UnexpectedProjectLocation(String),
     #[error("Failed to execute `RUSTC=$ZISK_RUSTC cargo build --release ...` in {cwd}: {source}")]
     CargoBuild {
         cwd: PathBuf,
@@ -78,6 +123,14 @@ pub enum CompileError {
     CargoBuildFailed { status: ExitStatus, path: PathBuf },
 }
 
+#[derive(Debug, Error)]
+pub enum ResourceError {
+    #[error("GPU proving not yet implemented for ZisK. Use CPU resource type.")]
+    GpuUnsupported,
+    #[error("Network proving not yet implemented for ZisK. Use CPU resource type.")]
+    NetworkUnsupported,
+}
+
 #[derive(Debug, Error)]
 pub enum ExecuteError {
     #[error("IO failure in temporary directory: {0}")]
@@ -90,7 +143,10 @@ pub enum ExecuteError {
         source: io::Error,
     },
     #[error("`ziskemu` failed with status: {status}")]
-    ZiskemuFailed { status: ExitStatus },
+    ZiskemuFailed {
+        status: ExitStatus,
+        output: GuestOutput,
+    },
     #[error("Total steps not found in report")]
     TotalStepsNotFound,
 }
@@ -115,6 +171,8 @@ pub enum ProveError {
     },
     #[error("`cargo prove` failed with status: {status}")]
     CargoZiskProveFailed { status: ExitStatus },
+    #[error("Failed to decode public values: {0}")]
+    PublicValues(#[source] serde_json::Error),
     #[error("Serialising proof with `bincode` failed: {0}")]
     Bincode(#[from] bincode::Error),
 }
@@ -132,4 +190,33 @@ pub enum VerifyError {
     },
     #[error("Invalid proof: {0}")]
     InvalidProof(String),
+    #[error("Failed to decode public values: {0}")]
+    PublicValues(#[source] serde_json::Error),
+    #[error("`cargo-zisk verify` does not take the program, so proofs are not bound to it")]
+    ProgramNotBound,
+}
+
+#[derive(Debug, Error)]
+pub enum KeysError {
+    #[error("IO failure in temporary directory: {0}")]
+    TempDir(io::Error),
+    #[error("Failed to access ROM setup file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("ROM setup in {0} was generated for a different ELF")]
+    ProgramMismatch(PathBuf),
+    #[error("ROM setup is missing the assembly file {0}")]
+    MissingAsm(PathBuf),
+}
+
+impl KeysError {
+    pub(crate) fn io(path: &Path, source: io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
 }
@@ -1,24 +1,31 @@
 use crate::{
     compile::compile_zisk_program,
-    error::{ExecuteError, ProveError, VerifyError, ZiskError},
+    error::{ExecuteError, KeysError, ProveError, ResourceError, VerifyError, ZiskError},
 };
 use serde::{Deserialize, Serialize};
 use std::{
     fs,
-    io::{self, Write},
+    io::{self, Read, Write},
     os::unix::fs::symlink,
     path::{Path, PathBuf},
-    process::{Command, Stdio},
-    time,
+    process::{Command, Output, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+    thread, time,
 };
 use tempfile::{TempDir, tempdir};
 use zkvm_interface::{
-    Compiler, Input, ProgramExecutionReport, ProgramProvingReport, ProverResourceType, zkVM,
-    zkVMError,
+    Compiler, ExecutionLimits, GuestOutput, Input, InputEncoding, InputError, ProgramDigest,
+    ProgramExecutionReport, ProgramProvingReport, ProofEnvelope, ProofKind, ProverResourceType,
+    PublicValues, PublicValuesEncoding, zkVM, zkVMError, zkVMVerifier,
 };
 
 include!(concat!(env!("OUT_DIR"), "/name_and_sdk_version.rs"));
 
+/// File name of the program digest written by [`EreZisk::export_keys`].
+const PROGRAM_DIGEST_FILE: &str = "program_digest";
+/// File stem of the ELF, which `cargo-zisk rom-setup` names its output after.
+const ELF_FILE_STEM: &str = "guest";
+
 mod compile;
 mod error;
 
@@ -43,64 +50,279 @@ pub struct ZiskProofWithPublicValues {
     pub public_values: Vec<u8>,
 }
 
+/// A single entry of `publics.json`, which may be written either as a number
+/// or as a decimal string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PublicSlot {
+    Number(u64),
+    String(String),
+}
+
+/// Blake3 hash of the ELF, which identifies ZisK programs.
+fn elf_program_id(elf: &[u8]) -> ProgramDigest {
+    ProgramDigest::new(*blake3::hash(elf).as_bytes())
+}
+
+/// Decodes the `publics.json` generated by proving into the public values
+/// committed by the guest with `ziskos::set_output`, each output slot encoded
+/// as a little-endian `u32`.
+fn decode_public_values(publics: &[u8]) -> Result<PublicValues, serde_json::Error> {
+    use serde::de::Error;
+
+    let slots: Vec<PublicSlot> = serde_json::from_slice(publics)?;
+    slots.into_iter().try_fold(Vec::new(), |mut acc, slot| {
+        let value = match slot {
+            PublicSlot::Number(value) => value,
+            PublicSlot::String(value) => value.parse().map_err(serde_json::Error::custom)?,
+        };
+        let value = u32::try_from(value).map_err(serde_json::Error::custom)?;
+        acc.extend(value.to_le_bytes());
+        Ok(acc)
+    })
+}
+
+/// Number of output slots committed by a ZisK proof, each one a `u32`.
+const OUTPUT_SLOTS: usize = 64;
+
+/// Decodes the output slots written by `ziskemu --output`, as little-endian
+/// `u32`s up to the last slot set by the guest, into the same encoding as
+/// [`decode_public_values`].
+///
+/// The proof commits to every slot, so the unset ones are padded with zeros,
+/// for the public values of execution and proving to be the same bytes.
+fn decode_emulator_output(mut output: Vec<u8>) -> PublicValues {
+    output.resize(output.len().max(OUTPUT_SLOTS * 4), 0);
+    output
+}
+
 pub struct EreZisk {
     elf: Vec<u8>,
     resource: ProverResourceType,
+    /// Output of `cargo-zisk rom-setup` for `elf`, generated once and reused by
+    /// every later proving.
+    rom_setup: Mutex<Option<Arc<ZiskTempDir>>>,
 }
 
 impl EreZisk {
+    /// # Panics
+    ///
+    /// Panics if the resource type is not supported, see [`EreZisk::try_new`].
     pub fn new(elf: Vec<u8>, resource: ProverResourceType) -> Self {
-        Self { elf, resource }
+        Self::try_new(elf, resource).expect("Unsupported ZisK resource type")
+    }
+
+    /// Like [`EreZisk::new`], but returns an error instead of panicking if the
+    /// resource type is GPU or network proving, which are not implemented yet.
+    pub fn try_new(elf: Vec<u8>, resource: ProverResourceType) -> Result<Self, ZiskError> {
+        check_resource(&resource)?;
+        Ok(Self {
+            elf,
+            resource,
+            rom_setup: Mutex::new(None),
+        })
+    }
+
+    /// Runs the ROM setup of the ELF ahead of proving.
+    ///
+    /// Calling this is optional, `prove` runs it on first use otherwise.
+    pub fn setup(&self) -> Result<(), ZiskError> {
+        self.rom_setup().map(|_| ()).map_err(ZiskError::Prove)
+    }
+
+    /// Returns the ROM setup of the ELF, running `cargo-zisk rom-setup` only if
+    /// it has not been done yet.
+    fn rom_setup(&self) -> Result<Arc<ZiskTempDir>, ProveError> {
+        let mut rom_setup = self
+            .rom_setup
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(rom_setup) = rom_setup.as_ref() {
+            return Ok(rom_setup.clone());
+        }
+
+        let mut tempdir = ZiskTempDir::new(true).map_err(ProveError::TempDir)?;
+        tempdir.write_elf(&self.elf).map_err(ProveError::TempDir)?;
+
+        let status = Command::new("cargo-zisk")
+            .arg("rom-setup")
+            .arg("--elf")
+            .arg(tempdir.elf_path())
+            .arg("--output-dir")
+            .arg(tempdir.rom_dir_path())
+            .arg("--zisk-path")
+            .arg(tempdir.zisk_dir_path())
+            .status()
+            .map_err(|e| ProveError::CargoZiskRomSetup { source: e })?;
+
+        if !status.success() {
+            return Err(ProveError::CargoZiskRomSetupFailed { status });
+        }
+
+        Ok(rom_setup.insert(Arc::new(tempdir)).clone())
+    }
+
+    /// Writes the output of the ROM setup to `dir`, alongside the
+    /// [`zkVM::program_id`] of the ELF, so that [`EreZisk::load_keys`] can skip the ROM setup next
+    /// time. Runs the ROM setup first if it has not been done yet.
+    pub fn export_keys(&self, dir: &Path) -> Result<(), ZiskError> {
+        let rom_setup = self.rom_setup().map_err(ZiskError::Prove)?;
+
+        fs::create_dir_all(dir).map_err(|source| KeysError::io(dir, source))?;
+        let digest_path = dir.join(PROGRAM_DIGEST_FILE);
+        fs::write(&digest_path, self.program_id().as_bytes())
+            .map_err(|source| KeysError::io(&digest_path, source))?;
+        copy_files(&rom_setup.rom_dir_path(), dir)?;
+
+        Ok(())
+    }
+
+    /// Creates an instance from a ROM setup previously written by
+    /// [`EreZisk::export_keys`] instead of running `cargo-zisk rom-setup`.
+    ///
+    /// A ROM setup that was generated for a different ELF is rejected: the
+    /// exported program id has to be the one of `elf`, and so does the ELF hash
+    /// `cargo-zisk rom-setup` puts in the name of every file it writes.
+    pub fn load_keys(
+        elf: Vec<u8>,
+        resource: ProverResourceType,
+        dir: &Path,
+    ) -> Result<Self, ZiskError> {
+        check_resource(&resource)?;
+
+        let digest_path = dir.join(PROGRAM_DIGEST_FILE);
+        let digest =
+            fs::read(&digest_path).map_err(|source| KeysError::io(&digest_path, source))?;
+        if digest != elf_program_id(&elf).as_bytes() {
+            return Err(KeysError::ProgramMismatch(dir.to_path_buf()).into());
+        }
+        ensure_rom_setup_of(dir, &blake3::hash(&elf).to_hex())?;
+
+        let mut tempdir = ZiskTempDir::new(false).map_err(KeysError::TempDir)?;
+        tempdir.write_elf(&elf).map_err(KeysError::TempDir)?;
+        copy_files(dir, &tempdir.rom_dir_path())?;
+
+        let asm_path = tempdir.asm_path();
+        if !asm_path.is_file() {
+            return Err(KeysError::MissingAsm(asm_path).into());
+        }
+
+        Ok(Self {
+            elf,
+            resource,
+            rom_setup: Mutex::new(Some(Arc::new(tempdir))),
+        })
     }
 }
 
-impl EreZisk {}
+/// Checks that the ROM setup files in `dir` that are named after the hash of
+/// an ELF, as `<stem>-<hash>-<kind>.bin`, are all named after `elf_hash`.
+fn ensure_rom_setup_of(dir: &Path, elf_hash: &str) -> Result<(), KeysError> {
+    let entries = fs::read_dir(dir).map_err(|source| KeysError::io(dir, source))?;
+    for entry in entries {
+        let path = entry.map_err(|source| KeysError::io(dir, source))?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let hash = file_name
+            .strip_prefix(ELF_FILE_STEM)
+            .and_then(|name| name.strip_prefix('-'))
+            .and_then(|name| name.split('-').next());
+        if hash.is_some_and(|hash| hash != elf_hash) {
+            return Err(KeysError::ProgramMismatch(dir.to_path_buf()));
+        }
+    }
+    Ok(())
+}
+
+/// Copies the ROM setup files directly under `from` into `to`, leaving out the
+/// program digest.
+fn copy_files(from: &Path, to: &Path) -> Result<(), KeysError> {
+    let entries = fs::read_dir(from).map_err(|source| KeysError::io(from, source))?;
+    for entry in entries {
+        let path = entry.map_err(|source| KeysError::io(from, source))?.path();
+        let file_name = path.file_name().expect("read_dir entries have a file name");
+        if path.is_file() && file_name != PROGRAM_DIGEST_FILE {
+            fs::copy(&path, to.join(file_name)).map_err(|source| KeysError::io(&path, source))?;
+        }
+    }
+    Ok(())
+}
 
 impl zkVM for EreZisk {
-    fn execute(&self, input: &Input) -> Result<ProgramExecutionReport, zkVMError> {
+    fn execute_with_limits(
+        &self,
+        input: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, ProgramExecutionReport), zkVMError> {
         // Write ELF and serialized input to file.
 
-        let input_bytes = input
-            .iter()
-            .try_fold(Vec::new(), |mut acc, item| {
-                acc.extend(item.as_bytes().map_err(ExecuteError::SerializeInput)?);
-                Ok(acc)
-            })
-            .map_err(ZiskError::Execute)?;
-
         let mut tempdir =
             ZiskTempDir::new(false).map_err(|e| ZiskError::Execute(ExecuteError::TempDir(e)))?;
         tempdir
             .write_elf(&self.elf)
             .map_err(|e| ZiskError::Execute(ExecuteError::TempDir(e)))?;
-        tempdir
-            .write_input(&input_bytes)
-            .map_err(|e| ZiskError::Execute(ExecuteError::TempDir(e)))?;
+        tempdir.write_input(input).map_err(|e| {
+            ZiskError::Execute(match e {
+                InputError::Io(e) => ExecuteError::TempDir(e),
+                e => ExecuteError::SerializeInput(e.into()),
+            })
+        })?;
 
         // Execute.
 
-        let start = time::Instant::now();
-        let output = Command::new("ziskemu")
+        let mut command = Command::new("ziskemu");
+        command
             .arg("--elf")
             .arg(tempdir.elf_path())
             .arg("--inputs")
             .arg(tempdir.input_path())
-            .arg("--stats") // NOTE: enable stats in order to get total steps.
-            .stderr(Stdio::inherit())
-            .output()
-            .map_err(|e| ZiskError::Execute(ExecuteError::Ziskemu { source: e }))?;
+            // Write the output slots to a file, since the guest prints to the
+            // same stdout as `ziskemu`.
+            .arg("--output")
+            .arg(tempdir.emulator_output_path())
+            .arg("--stats"); // NOTE: enable stats in order to get total steps.
+        if let Some(max_cycles) = limits.max_cycles {
+            // Let the emulator run one step past the limit, so that running into
+            // it can be told apart from finishing right at it.
+            command
+                .arg("--max-steps")
+                .arg(max_cycles.saturating_add(1).to_string());
+        }
+
+        let start = time::Instant::now();
+        let ziskemu_error = |e| ZiskError::Execute(ExecuteError::Ziskemu { source: e });
+        let output = match limits.max_duration {
+            Some(max_duration) => output_with_timeout(&mut command, max_duration)
+                .map_err(ziskemu_error)?
+                .ok_or(zkVMError::Timeout(max_duration))?,
+            None => command.output().map_err(ziskemu_error)?,
+        };
+        // The guest prints through the emulator, so its output is interleaved
+        // with the stats `ziskemu` prints.
+        let guest_output = GuestOutput {
+            stdout: output.stdout,
+            stderr: output.stderr,
+        };
 
         if !output.status.success() {
             return Err(ZiskError::Execute(ExecuteError::ZiskemuFailed {
                 status: output.status,
+                output: guest_output,
             })
             .into());
         }
         let execution_duration = start.elapsed();
 
-        // Extract cycle count from the stdout.
+        // Extract public values from the output file and cycle count from the
+        // stdout.
 
-        let total_num_cycles = String::from_utf8_lossy(&output.stdout)
+        let public_values = tempdir
+            .read_emulator_output()
+            .map(decode_emulator_output)
+            .map_err(|e| ZiskError::Execute(ExecuteError::TempDir(e)))?;
+        let stdout = String::from_utf8_lossy(&guest_output.stdout);
+        let total_num_cycles = stdout
             .split_once("total steps = ")
             .and_then(|(_, stats)| {
                 stats
@@ -109,50 +331,50 @@ impl zkVM for EreZisk {
                     .and_then(|steps| steps.parse::<u64>().ok())
             })
             .ok_or(ZiskError::Execute(ExecuteError::TotalStepsNotFound))?;
-
-        Ok(ProgramExecutionReport {
-            total_num_cycles,
-            execution_duration,
-            ..Default::default()
-        })
+        limits.check(total_num_cycles, execution_duration)?;
+
+        Ok((
+            public_values,
+            ProgramExecutionReport {
+                total_num_cycles,
+                execution_duration,
+                guest_output,
+                ..Default::default()
+            },
+        ))
     }
 
-    fn prove(&self, input: &Input) -> Result<(Vec<u8>, ProgramProvingReport), zkVMError> {
-        // Write ELF and serialized input to file.
-
-        let input_bytes = input
-            .iter()
-            .try_fold(Vec::new(), |mut acc, item| {
-                acc.extend(item.as_bytes().map_err(ProveError::SerializeInput)?);
-                Ok(acc)
-            })
-            .map_err(ZiskError::Prove)?;
+    fn prove_with_limits(
+        &self,
+        input: &Input,
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, ProgramProvingReport), zkVMError> {
+        // `cargo-zisk verify` only accepts the aggregated proof.
+        if proof_kind != ProofKind::Compressed {
+            return Err(zkVMError::UnsupportedProofKind {
+                zkvm: NAME,
+                proof_kind,
+            });
+        }
 
-        let mut tempdir =
-            ZiskTempDir::new(true).map_err(|e| ZiskError::Prove(ProveError::TempDir(e)))?;
-        tempdir
-            .write_elf(&self.elf)
-            .map_err(|e| ZiskError::Prove(ProveError::TempDir(e)))?;
-        tempdir
-            .write_input(&input_bytes)
-            .map_err(|e| ZiskError::Prove(ProveError::TempDir(e)))?;
+        // `cargo-zisk prove` has no limits of its own and only reports a failed
+        // status when the guest panics, so run the emulator first to bound the
+        // execution and report a panic as such.
+        self.execute_with_limits(input, limits)?;
 
-        // Setup ROM.
+        // Setup ROM once, then write serialized input to file.
 
-        let status = Command::new("cargo-zisk")
-            .arg("rom-setup")
-            .arg("--elf")
-            .arg(tempdir.elf_path())
-            .arg("--output-dir")
-            .arg(tempdir.rom_dir_path())
-            .arg("--zisk-path")
-            .arg(tempdir.zisk_dir_path())
-            .status()
-            .map_err(|e| ZiskError::Prove(ProveError::CargoZiskRomSetup { source: e }))?;
+        let rom_setup = self.rom_setup().map_err(ZiskError::Prove)?;
 
-        if !status.success() {
-            return Err(ZiskError::Prove(ProveError::CargoZiskRomSetupFailed { status }).into());
-        }
+        let mut tempdir =
+            ZiskTempDir::new(false).map_err(|e| ZiskError::Prove(ProveError::TempDir(e)))?;
+        tempdir.write_input(input).map_err(|e| {
+            ZiskError::Prove(match e {
+                InputError::Io(e) => ProveError::TempDir(e),
+                e => ProveError::SerializeInput(e.into()),
+            })
+        })?;
 
         // Prove.
 
@@ -162,9 +384,9 @@ impl zkVM for EreZisk {
                 let status = Command::new("cargo-zisk")
                     .arg("prove")
                     .arg("--elf")
-                    .arg(tempdir.elf_path())
+                    .arg(rom_setup.elf_path())
                     .arg("--asm")
-                    .arg(tempdir.asm_path())
+                    .arg(rom_setup.asm_path())
                     .arg("--input")
                     .arg(tempdir.input_path())
                     .arg("--output-dir")
@@ -180,15 +402,8 @@ impl zkVM for EreZisk {
                     );
                 }
             }
-            ProverResourceType::Gpu => {
-                // TODO: Need to install another version of `cargo-zisk` with
-                //       `features = gpu` and call it here.
-                unimplemented!()
-            }
-            ProverResourceType::Network(_) => {
-                panic!(
-                    "Network proving not yet implemented for ZisK. Use CPU or GPU resource type."
-                );
+            ProverResourceType::Gpu | ProverResourceType::Network(_) => {
+                check_resource(&self.resource).map_err(ZiskError::Resource)?;
             }
         }
         let proving_time = start.elapsed();
@@ -203,17 +418,44 @@ impl zkVM for EreZisk {
                 .read_public_values()
                 .map_err(|e| ZiskError::Prove(ProveError::TempDir(e)))?,
         };
+        let public_values = decode_public_values(&proof_with_public_values.public_values)
+            .map_err(|err| ZiskError::Prove(ProveError::PublicValues(err)))?;
         let bytes = bincode::serialize(&proof_with_public_values)
             .map_err(|err| ZiskError::Prove(ProveError::Bincode(err)))?;
+        let envelope = ProofEnvelope::new(NAME, SDK_VERSION, self.program_id(), proof_kind, bytes);
+
+        Ok((
+            public_values,
+            envelope.encode(),
+            ProgramProvingReport::new(proving_time),
+        ))
+    }
 
-        Ok((bytes, ProgramProvingReport::new(proving_time)))
+    fn program_id(&self) -> ProgramDigest {
+        elf_program_id(&self.elf)
     }
 
-    fn verify(&self, bytes: &[u8]) -> Result<(), zkVMError> {
+    fn input_encoding(&self) -> InputEncoding {
+        InputEncoding::LengthPrefixedBincode
+    }
+}
+
+impl zkVMVerifier for EreZisk {
+    /// Verifies the aggregated proof with `cargo-zisk verify`.
+    ///
+    /// ZisK v0.8.1 verifies every program against the same verifying key, and
+    /// its proofs do not commit to the ROM, so the proof is not bound to the
+    /// ELF. The program id of the envelope is checked, but it is only the label
+    /// the prover wrote, see [`zkVMVerifier::ensure_program_binding`].
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
         // Write proof and public values to file.
 
-        let proof_with_public_values: ZiskProofWithPublicValues = bincode::deserialize(bytes)
-            .map_err(|err| ZiskError::Verify(VerifyError::Bincode(err)))?;
+        let envelope = ProofEnvelope::decode(proof, NAME, SDK_VERSION)?;
+        envelope.ensure_program(&self.program_id())?;
+
+        let proof_with_public_values: ZiskProofWithPublicValues =
+            bincode::deserialize(&envelope.payload)
+                .map_err(|err| ZiskError::Verify(VerifyError::Bincode(err)))?;
 
         let mut tempdir =
             ZiskTempDir::new(false).map_err(|e| ZiskError::Verify(VerifyError::TempDir(e)))?;
@@ -242,7 +484,18 @@ impl zkVM for EreZisk {
             .into());
         }
 
-        Ok(())
+        let public_values = decode_public_values(&proof_with_public_values.public_values)
+            .map_err(|err| ZiskError::Verify(VerifyError::PublicValues(err)))?;
+
+        Ok(public_values)
+    }
+
+    fn ensure_program_binding(&self) -> Result<(), zkVMError> {
+        Err(ZiskError::Verify(VerifyError::ProgramNotBound).into())
+    }
+
+    fn public_values_encoding(&self) -> PublicValuesEncoding {
+        PublicValuesEncoding::WordAlignedBincode
     }
 
     fn name(&self) -> &'static str {
@@ -254,6 +507,67 @@ impl zkVM for EreZisk {
     }
 }
 
+/// Only CPU proving is supported so far.
+fn check_resource(resource: &ProverResourceType) -> Result<(), ResourceError> {
+    match resource {
+        ProverResourceType::Cpu => Ok(()),
+        // TODO: Need to install another version of `cargo-zisk` with
+        //       `features = gpu` and call it for GPU proving.
+        ProverResourceType::Gpu => Err(ResourceError::GpuUnsupported),
+        ProverResourceType::Network(_) => Err(ResourceError::NetworkUnsupported),
+    }
+}
+
+/// Runs `command` to completion like [`Command::output`], but kills it and
+/// returns `None` once `timeout` has passed.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: time::Duration,
+) -> io::Result<Option<Output>> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Drain the pipes while waiting, so that the child never blocks on a full one.
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+
+    let deadline = time::Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if time::Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(time::Duration::from_millis(10));
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: stdout
+            .join()
+            .map_err(|_| io::Error::other("stdout reader panicked"))??,
+        stderr: stderr
+            .join()
+            .map_err(|_| io::Error::other("stderr reader panicked"))??,
+    }))
+}
+
+fn read_to_end(
+    reader: Option<impl Read + Send + 'static>,
+) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut reader) = reader {
+            reader.read_to_end(&mut buf)?;
+        }
+        Ok(buf)
+    })
+}
+
 struct ZiskTempDir {
     tempdir: TempDir,
     elf_hash: Option<String>,
@@ -265,6 +579,7 @@ impl ZiskTempDir {
     /// - `rom/` - Directory for output of `rom-setup`.
     /// - `zisk/` - Directory for building process during `rom-setup`.
     /// - `input.bin` - Input of execution or proving.
+    /// - `emulator_output.bin` - Output slots written by execution.
     /// - `output/proofs/vadcop_final_proof.json` - Aggregated proof generated by proving.
     /// - `output/publics.json` - Public values generated by proving.
     ///
@@ -320,8 +635,15 @@ impl ZiskTempDir {
         fs::write(self.elf_path(), elf)
     }
 
-    fn write_input(&mut self, input: &[u8]) -> io::Result<()> {
-        fs::File::create(self.input_path()).and_then(|mut file| file.write_all(input))
+    /// Stream the input into `input.bin`, without buffering it in memory first.
+    fn write_input(&mut self, input: &Input) -> Result<(), InputError> {
+        let mut writer = io::BufWriter::new(fs::File::create(self.input_path())?);
+        input.write_length_prefixed(&mut writer)?;
+        Ok(writer.flush()?)
+    }
+
+    fn read_emulator_output(&self) -> io::Result<Vec<u8>> {
+        fs::read(self.emulator_output_path())
     }
 
     fn read_proof(&self) -> io::Result<Vec<u8>> {
@@ -342,7 +664,7 @@ impl ZiskTempDir {
     }
 
     fn elf_path(&self) -> PathBuf {
-        self.tempdir.path().join("guest.elf")
+        self.tempdir.path().join(format!("{ELF_FILE_STEM}.elf"))
     }
 
     fn rom_dir_path(&self) -> PathBuf {
@@ -368,6 +690,10 @@ impl ZiskTempDir {
         self.tempdir.path().join("input.bin")
     }
 
+    fn emulator_output_path(&self) -> PathBuf {
+        self.tempdir.path().join("emulator_output.bin")
+    }
+
     fn output_dir_path(&self) -> PathBuf {
         self.tempdir.path().join("output")
     }
@@ -385,6 +711,22 @@ impl ZiskTempDir {
     }
 }
 
+#[cfg(test)]
+mod input_tests {
+    use super::*;
+    use zkvm_interface::test_utils::{LengthPrefixedInput, check_input_parity};
+
+    #[test]
+    fn test_input_parity() {
+        check_input_parity(InputEncoding::LengthPrefixedBincode, |input| {
+            // The guest reads the whole input file with `ziskos::read_input`.
+            let mut tempdir = ZiskTempDir::new(false).unwrap();
+            tempdir.write_input(input).unwrap();
+            LengthPrefixedInput::new(fs::read(tempdir.input_path()).unwrap())
+        });
+    }
+}
+
 #[cfg(test)]
 mod execute_tests {
     use super::*;
@@ -418,11 +760,14 @@ mod execute_tests {
 
         let zkvm = EreZisk::new(elf_bytes, ProverResourceType::Cpu);
 
-        let result = zkvm.execute(&input_builder);
+        let (public_values, _) = match zkvm.execute(&input_builder) {
+            Ok(result) => result,
+            Err(e) => panic!("Execution error: {e:?}"),
+        };
 
-        if let Err(e) = &result {
-            panic!("Execution error: {e:?}");
-        }
+        // The guest writes `(n + a) * 2` into the first output slot.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<u32>().unwrap(), (n + a as u32) * 2);
     }
 
     #[test]
@@ -433,7 +778,53 @@ mod execute_tests {
         let empty_input = Input::new();
 
         let zkvm = EreZisk::new(elf_bytes, ProverResourceType::Cpu);
-        assert!(zkvm.execute(&empty_input).is_err());
+        assert!(matches!(
+            zkvm.execute(&empty_input),
+            Err(zkVMError::GuestPanicked { exit_code: Some(code), .. }) if code != 0
+        ));
+    }
+
+    #[test]
+    fn test_execute_zisk_cycle_limit() {
+        let elf_bytes = get_compiled_test_zisk_elf()
+            .expect("Failed to compile test ZisK guest for execution test");
+
+        let mut input_builder = Input::new();
+        input_builder.write(42u32);
+        input_builder.write(42u16);
+
+        let zkvm = EreZisk::new(elf_bytes, ProverResourceType::Cpu);
+        let (_, report) = zkvm.execute(&input_builder).unwrap();
+
+        let limits = ExecutionLimits::new().with_max_cycles(report.total_num_cycles);
+        zkvm.execute_with_limits(&input_builder, &limits)
+            .expect("execution should fit its own step count");
+
+        let limits = ExecutionLimits::new().with_max_cycles(report.total_num_cycles / 2);
+        assert!(matches!(
+            zkvm.execute_with_limits(&input_builder, &limits),
+            Err(zkVMError::CycleLimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_output_with_timeout() {
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            time::Duration::from_secs(10),
+        )
+        .unwrap()
+        .expect("command should finish before the timeout");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+
+        let output = output_with_timeout(
+            Command::new("sleep").arg("10"),
+            time::Duration::from_millis(100),
+        )
+        .unwrap();
+        assert!(output.is_none());
     }
 }
 
@@ -442,7 +833,7 @@ mod prove_tests {
     use std::path::PathBuf;
 
     use super::*;
-    use zkvm_interface::Input;
+    use zkvm_interface::{Input, test_utils::check_prove_verify};
 
     fn get_prove_test_guest_program_path() -> PathBuf {
         let workspace_dir = env!("CARGO_WORKSPACE_DIR");
@@ -473,27 +864,82 @@ mod prove_tests {
 
         let zkvm = EreZisk::new(elf_path, ProverResourceType::Cpu);
 
-        let proof_bytes = match zkvm.prove(&input_builder) {
-            Ok((prove_result, _)) => prove_result,
-            Err(err) => {
-                panic!("Proving error in test: {err:?}");
-            }
-        };
-
-        assert!(!proof_bytes.is_empty(), "Proof bytes should not be empty.");
+        let (public_values, proof_bytes) =
+            check_prove_verify(&zkvm, &input_builder, ProofKind::Compressed);
 
-        assert!(zkvm.verify(&proof_bytes).is_ok());
+        // The guest writes `(n + a) * 2` into the first output slot.
+        let mut reader = zkvm.public_values_reader(&public_values);
+        assert_eq!(reader.read::<u32>().unwrap(), (n + a as u32) * 2);
 
         let invalid_proof_bytes = {
+            let mut envelope = ProofEnvelope::decode(&proof_bytes, NAME, SDK_VERSION).unwrap();
             let mut invalid_proof: ZiskProofWithPublicValues =
-                bincode::deserialize(&proof_bytes).unwrap();
+                bincode::deserialize(&envelope.payload).unwrap();
             // alter the first digit of `evals[0][0]`
             invalid_proof.proof[40] = invalid_proof.proof[40].overflowing_add(1).0;
-            bincode::serialize(&invalid_proof).unwrap()
+            envelope.payload = bincode::serialize(&invalid_proof).unwrap();
+            envelope.encode()
         };
-        assert!(zkvm.verify(&invalid_proof_bytes).is_err());
+        assert!(matches!(
+            zkvm.verify(&invalid_proof_bytes),
+            Err(zkVMError::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_and_load_keys_zisk() {
+        let elf = get_compiled_test_zisk_elf_for_prove()
+            .expect("Failed to compile test ZisK guest for proving test");
+        let keys_dir = tempdir().unwrap();
+
+        let zkvm = EreZisk::new(elf.clone(), ProverResourceType::Cpu);
+        zkvm.export_keys(keys_dir.path()).unwrap();
+
+        let loaded = EreZisk::load_keys(elf.clone(), ProverResourceType::Cpu, keys_dir.path())
+            .expect("ROM setup should load for the ELF it was generated for");
+        assert!(loaded.rom_setup().unwrap().asm_path().is_file());
+
+        let mut other_elf = elf.clone();
+        other_elf.push(0);
+        assert!(matches!(
+            EreZisk::load_keys(other_elf.clone(), ProverResourceType::Cpu, keys_dir.path()),
+            Err(ZiskError::Keys(KeysError::ProgramMismatch(_)))
+        ));
+
+        // A ROM setup file of another ELF is rejected too.
+        let other_hash = blake3::hash(&other_elf).to_hex();
+        fs::write(
+            keys_dir
+                .path()
+                .join(format!("{ELF_FILE_STEM}-{other_hash}-mt.bin")),
+            [0],
+        )
+        .unwrap();
+        assert!(matches!(
+            EreZisk::load_keys(elf, ProverResourceType::Cpu, keys_dir.path()),
+            Err(ZiskError::Keys(KeysError::ProgramMismatch(_)))
+        ));
+    }
 
-        // TODO: Check public inputs
+    #[test]
+    fn test_program_binding_unsupported() {
+        let zkvm = EreZisk::new(Vec::new(), ProverResourceType::Cpu);
+        assert!(matches!(
+            zkvm.ensure_program_binding(),
+            Err(zkVMError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_try_new_unsupported_resource() {
+        assert!(matches!(
+            EreZisk::try_new(Vec::new(), ProverResourceType::Gpu),
+            Err(ZiskError::Resource(ResourceError::GpuUnsupported))
+        ));
+        assert!(matches!(
+            EreZisk::try_new(Vec::new(), ProverResourceType::Network(Default::default())),
+            Err(ZiskError::Resource(ResourceError::NetworkUnsupported))
+        ));
     }
 
     #[test]
@@ -504,6 +950,9 @@ mod prove_tests {
         let empty_input = Input::new();
 
         let zkvm = EreZisk::new(elf_path, ProverResourceType::Cpu);
-        assert!(zkvm.prove(&empty_input).is_err());
+        assert!(matches!(
+            zkvm.prove(&empty_input, ProofKind::Compressed),
+            Err(zkVMError::GuestPanicked { exit_code: Some(code), .. }) if code != 0
+        ));
     }
 }
@@ -0,0 +1,67 @@
+//! Capture of what a guest prints, for executors that write it to the stdout
+//! and stderr of the host instead of handing it back.
+
+use std::{
+    io::{self, Read, Write},
+    sync::{Mutex, PoisonError},
+};
+
+use gag::BufferRedirect;
+
+use crate::GuestOutput;
+
+/// Only one capture can redirect the file descriptors at a time.
+static CAPTURE: Mutex<()> = Mutex::new(());
+
+/// Runs `f` with the stdout and stderr file descriptors of the process
+/// redirected, and returns what was written to them alongside its result.
+///
+/// Everything the process writes while `f` runs is captured, not only what
+/// the guest prints, and nothing of it reaches the terminal. Captures are
+/// serialized, so concurrent executions wait for each other.
+pub fn capture_output<T>(f: impl FnOnce() -> T) -> io::Result<(T, GuestOutput)> {
+    let _guard = CAPTURE.lock().unwrap_or_else(PoisonError::into_inner);
+
+    io::stdout().flush()?;
+    let stdout = BufferRedirect::stdout()?;
+    let stderr = BufferRedirect::stderr()?;
+
+    let value = f();
+
+    io::stdout().flush()?;
+    let mut output = GuestOutput::default();
+    stdout.into_inner().read_to_end(&mut output.stdout)?;
+    stderr.into_inner().read_to_end(&mut output.stderr)?;
+    Ok((value, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::Write, mem::ManuallyDrop, os::fd::FromRawFd};
+
+    use super::*;
+
+    /// Writes to the file descriptor directly, the way an executor printing
+    /// from the guest does, bypassing the output capture of libtest.
+    fn write_fd(fd: i32, bytes: &[u8]) {
+        let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+        file.write_all(bytes).unwrap();
+    }
+
+    #[test]
+    fn test_capture_output() {
+        let (value, output) = capture_output(|| {
+            write_fd(1, b"n = 42\n");
+            write_fd(2, b"panicked at src/main.rs:3:5:\nboom\n");
+            42
+        })
+        .unwrap();
+
+        assert_eq!(value, 42);
+        assert_eq!(output.stdout, b"n = 42\n");
+        assert_eq!(
+            output.panic_message().as_deref(),
+            Some("boom at src/main.rs:3:5")
+        );
+    }
+}
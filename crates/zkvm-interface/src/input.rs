@@ -1,8 +1,94 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    fs::File,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use bincode::Options;
 use erased_serde::Serialize as ErasedSerialize;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{PublicValuesEncoding, PublicValuesError};
+
+/// Layout in which a zkVM hands the items of an [`Input`] to its guest, which
+/// reads them back one by one in the order they were written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// Every item is a separate frame of the zkVM's input stream. Objects are
+    /// `bincode` encodings with fixed-width little-endian integers, read with
+    /// `sp1_zkvm::io::read` and `pico_sdk::io::read_as`, and bytes are passed as
+    /// is, read with `read_vec`.
+    Bincode,
+    /// Objects are laid out over `u32` words following the `risc0_zkvm::serde`
+    /// format, read with `env::read` on RISC Zero and `openvm::io::read` on
+    /// OpenVM. Bytes are a separate frame, read with `env::read_frame` and
+    /// `openvm::io::read_vec`.
+    Words,
+    /// Items are concatenated `postcard` encodings, with bytes passed as is,
+    /// and are deserialized as the arguments of the `#[jolt::provable]` function.
+    Postcard,
+    /// Items are concatenated into a single buffer, each prefixed by its length
+    /// as a little-endian `u32`. Objects are encoded as for
+    /// [`InputEncoding::Bincode`]. Guests get the buffer with
+    /// `ziskos::read_input` and decode it with [`InputReader`].
+    LengthPrefixedBincode,
+}
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("Unexpected end of input")]
+    UnexpectedEnd,
+
+    #[error("Input item of {0} bytes does not fit a `u32` length prefix")]
+    TooLarge(u64),
+
+    #[error("Failed to encode or decode input with `bincode`: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    #[error("Failed to encode input object: {0}")]
+    Encode(#[from] PublicValuesError),
+
+    #[error("Failed to read input file {path}: {source}")]
+    ReadFile {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Failed to write input: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl From<InputError> for crate::zkVMError {
+    fn from(err: InputError) -> Self {
+        crate::zkVMError::InvalidInput(Box::new(err))
+    }
+}
+
+/// Read the content of the file of an [`InputItem::File`].
+pub fn read_input_file(path: &Path) -> Result<Vec<u8>, InputError> {
+    std::fs::read(path).map_err(|source| InputError::ReadFile {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Open the file of an [`InputItem::File`] to stream its content, returning it
+/// with its length. Reads stop at that length even if the file grows meanwhile,
+/// so that the content matches a length prefix written up front.
+pub fn open_input_file(path: &Path) -> Result<(io::Take<File>, u64), InputError> {
+    let read_error = |source| InputError::ReadFile {
+        path: path.to_path_buf(),
+        source,
+    };
+    let file = File::open(path).map_err(read_error)?;
+    let len = file.metadata().map_err(read_error)?.len();
+    Ok((file.take(len), len))
+}
 
 #[derive(Clone)]
 pub enum InputItem {
@@ -10,6 +96,45 @@ pub enum InputItem {
     Object(Arc<Box<dyn ErasedSerialize + Send + Sync>>),
     /// Pre-serialized bytes (e.g., from bincode)
     Bytes(Vec<u8>),
+    /// Raw bytes of a file, handed to the guest as for [`InputItem::Bytes`].
+    /// The file is only read when the input is passed to the zkVM. ZisK and
+    /// RISC Zero stream it to the guest, Jolt and OpenVM read it straight into
+    /// their input buffer, and SP1 and Pico, whose stdin holds every item in
+    /// memory, read it into a buffer first.
+    File(PathBuf),
+    /// An object frozen into its encoded forms, as stored by
+    /// [`Input::to_bytes`]
+    Encoded(EncodedObject),
+}
+
+/// An [`InputItem::Object`] frozen into the bytes each [`InputEncoding`] hands
+/// to the guest, so that it can be stored and written again without the
+/// original value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodedObject {
+    bincode: Vec<u8>,
+    words: Vec<u8>,
+    postcard: Vec<u8>,
+}
+
+impl EncodedObject {
+    pub fn new<T: Serialize + ?Sized>(value: &T) -> Result<Self, InputError> {
+        Ok(Self {
+            bincode: PublicValuesEncoding::Bincode.encode(value)?,
+            words: PublicValuesEncoding::Words.encode(value)?,
+            postcard: PublicValuesEncoding::Postcard.encode(value)?,
+        })
+    }
+
+    /// The object as `encoding` writes it, without the length prefix of
+    /// [`InputEncoding::LengthPrefixedBincode`]
+    pub fn encoded(&self, encoding: InputEncoding) -> &[u8] {
+        match encoding {
+            InputEncoding::Bincode | InputEncoding::LengthPrefixedBincode => &self.bincode,
+            InputEncoding::Words => &self.words,
+            InputEncoding::Postcard => &self.postcard,
+        }
+    }
 }
 
 impl Debug for InputItem {
@@ -17,6 +142,8 @@ impl Debug for InputItem {
         match self {
             InputItem::Object(_) => f.write_str("Object(<erased>)"),
             InputItem::Bytes(bytes) => f.debug_tuple("Bytes").field(bytes).finish(),
+            InputItem::File(path) => f.debug_tuple("File").field(path).finish(),
+            InputItem::Encoded(obj) => f.debug_tuple("Encoded").field(obj).finish(),
         }
     }
 }
@@ -51,6 +178,12 @@ impl Input {
         self.items.push(InputItem::Bytes(bytes));
     }
 
+    /// Write the content of a file as raw bytes, read when the input is passed
+    /// to the zkVM rather than now
+    pub fn write_file(&mut self, path: impl Into<PathBuf>) {
+        self.items.push(InputItem::File(path.into()));
+    }
+
     /// Get the number of items stored
     pub fn len(&self) -> usize {
         self.items.len()
@@ -65,11 +198,162 @@ impl Input {
     pub fn iter(&self) -> std::slice::Iter<'_, InputItem> {
         self.items.iter()
     }
+
+    /// Encode the items into a single buffer following
+    /// [`InputEncoding::LengthPrefixedBincode`]
+    pub fn encode_length_prefixed(&self) -> Result<Vec<u8>, InputError> {
+        let mut buf = Vec::new();
+        self.write_length_prefixed(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Write the items to `writer` following
+    /// [`InputEncoding::LengthPrefixedBincode`], streaming objects and files
+    /// into it instead of buffering them
+    pub fn write_length_prefixed(&self, mut writer: impl Write) -> Result<(), InputError> {
+        let options = bincode::DefaultOptions::new().with_fixint_encoding();
+        for item in self.iter() {
+            match item {
+                InputItem::Object(obj) => {
+                    write_length_prefix(&mut writer, options.serialized_size(&**obj)?)?;
+                    options.serialize_into(&mut writer, &**obj)?;
+                }
+                InputItem::Bytes(bytes) => {
+                    write_length_prefix(&mut writer, bytes.len() as u64)?;
+                    writer.write_all(bytes)?;
+                }
+                InputItem::Encoded(obj) => {
+                    let bytes = obj.encoded(InputEncoding::LengthPrefixedBincode);
+                    write_length_prefix(&mut writer, bytes.len() as u64)?;
+                    writer.write_all(bytes)?;
+                }
+                InputItem::File(path) => {
+                    let (mut file, len) = open_input_file(path)?;
+                    write_length_prefix(&mut writer, len)?;
+                    let copied = io::copy(&mut file, &mut writer)?;
+                    if copied != len {
+                        return Err(InputError::ReadFile {
+                            path: path.clone(),
+                            source: io::ErrorKind::UnexpectedEof.into(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode the input so that it can be stored and replayed later with
+    /// [`Input::from_bytes`]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, InputError> {
+        Ok(bincode::serialize(&self.stored_items()?)?)
+    }
+
+    /// Decode an input encoded by [`Input::to_bytes`]. Objects come back as
+    /// [`InputItem::Encoded`] and files as [`InputItem::Bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, InputError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Objects frozen into their encoded forms, and files into their content
+    /// as of now, so that replaying the input hands the guest the same bytes.
+    fn stored_items(&self) -> Result<Vec<StoredItem<'_>>, InputError> {
+        self.iter()
+            .map(|item| {
+                Ok(match item {
+                    InputItem::Object(obj) => {
+                        StoredItem::Object(Cow::Owned(EncodedObject::new(&**obj)?))
+                    }
+                    InputItem::Encoded(obj) => StoredItem::Object(Cow::Borrowed(obj)),
+                    InputItem::Bytes(bytes) => StoredItem::Bytes(Cow::Borrowed(bytes)),
+                    InputItem::File(path) => StoredItem::Bytes(Cow::Owned(read_input_file(path)?)),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Stored form of an [`InputItem`], tagged with whether it is an object or
+/// raw bytes.
+#[derive(Serialize, Deserialize)]
+enum StoredItem<'a> {
+    Object(Cow<'a, EncodedObject>),
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl Serialize for Input {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.stored_items()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Input {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = Vec::<StoredItem>::deserialize(deserializer)?
+            .into_iter()
+            .map(|item| match item {
+                StoredItem::Object(obj) => InputItem::Encoded(obj.into_owned()),
+                StoredItem::Bytes(bytes) => InputItem::Bytes(bytes.into_owned()),
+            })
+            .collect();
+        Ok(Self { items })
+    }
+}
+
+fn write_length_prefix(writer: &mut impl Write, len: u64) -> Result<(), InputError> {
+    let len = u32::try_from(len).map_err(|_| InputError::TooLarge(len))?;
+    Ok(writer.write_all(&len.to_le_bytes())?)
+}
+
+/// Cursor over an input buffer encoded with
+/// [`InputEncoding::LengthPrefixedBincode`], decoding the items in the order
+/// they were written to the [`Input`].
+#[derive(Debug, Clone)]
+pub struct InputReader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> InputReader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input }
+    }
+
+    /// Read the next item, written with [`Input::write`]
+    pub fn read<T: DeserializeOwned>(&mut self) -> Result<T, InputError> {
+        let bytes = self.read_bytes()?;
+        Ok(bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .deserialize(bytes)?)
+    }
+
+    /// Read the next item as raw bytes, written with [`Input::write_bytes`]
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], InputError> {
+        let (len, rest) = self
+            .input
+            .split_first_chunk::<4>()
+            .ok_or(InputError::UnexpectedEnd)?;
+        let len = u32::from_le_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(InputError::UnexpectedEnd);
+        }
+        let (bytes, rest) = rest.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    /// Check if every item has been read
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
 }
 
 // Optional: Implement methods to work with the enum
 impl InputItem {
-    /// Serialize this item to bytes using the specified serializer
+    /// Serialize this item to bytes using the specified serializer. An
+    /// [`InputItem::Encoded`] has lost the original value, so its frozen
+    /// encodings are written instead.
     pub fn serialize_with<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
@@ -80,6 +364,10 @@ impl InputItem {
                 // Serialize the bytes as a byte array
                 bytes.serialize(serializer)
             }
+            InputItem::File(path) => read_input_file(path)
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
+            InputItem::Encoded(obj) => obj.serialize(serializer),
         }
     }
 
@@ -96,6 +384,8 @@ impl InputItem {
                 Ok(buf)
             }
             InputItem::Bytes(bytes) => Ok(bytes.to_vec()),
+            InputItem::File(path) => Ok(read_input_file(path)?),
+            InputItem::Encoded(obj) => Ok(obj.encoded(InputEncoding::Bincode).to_vec()),
         }
     }
 }
@@ -125,7 +415,7 @@ mod input_erased_tests {
 
         match &input.items[0] {
             InputItem::Object(_) => (), // Success
-            InputItem::Bytes(_) => panic!("Expected Object, got Bytes"),
+            item => panic!("Expected Object, got {item:?}"),
         }
     }
 
@@ -140,7 +430,7 @@ mod input_erased_tests {
 
         match &input.items[0] {
             InputItem::Bytes(stored_bytes) => assert_eq!(stored_bytes.to_vec(), bytes),
-            InputItem::Object(_) => panic!("Expected Bytes, got Object"),
+            item => panic!("Expected Bytes, got {item:?}"),
         }
     }
 
@@ -161,7 +451,7 @@ mod input_erased_tests {
 
         match &input.items[0] {
             InputItem::Bytes(_) => (), // Success
-            InputItem::Object(_) => panic!("Expected Bytes, got Object"),
+            item => panic!("Expected Bytes, got {item:?}"),
         }
     }
 
@@ -223,6 +513,149 @@ mod input_erased_tests {
         assert_eq!(raw_bytes, vec![1, 2, 3]);
     }
 
+    #[test]
+    fn test_length_prefixed_round_trip() {
+        let person = Person {
+            name: "Dave".to_string(),
+            age: 40,
+        };
+        let mut input = Input::new();
+        input.write(42u32);
+        input.write(7u16);
+        input.write_bytes(vec![1, 2, 3]);
+        input.write(person.clone());
+
+        let encoded = input.encode_length_prefixed().unwrap();
+        assert_eq!(encoded[..8], [4, 0, 0, 0, 42, 0, 0, 0]);
+
+        let mut reader = InputReader::new(&encoded);
+        assert_eq!(reader.read::<u32>().unwrap(), 42);
+        assert_eq!(reader.read::<u16>().unwrap(), 7);
+        assert_eq!(reader.read_bytes().unwrap(), [1, 2, 3]);
+        assert_eq!(reader.read::<Person>().unwrap(), person);
+        assert!(reader.is_empty());
+        assert!(matches!(
+            reader.read_bytes(),
+            Err(InputError::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn test_length_prefixed_type_mismatch() {
+        let mut input = Input::new();
+        input.write(42u16);
+
+        let encoded = input.encode_length_prefixed().unwrap();
+        let mut reader = InputReader::new(&encoded);
+        assert!(matches!(reader.read::<u32>(), Err(InputError::Bincode(_))));
+    }
+
+    #[test]
+    fn test_length_prefixed_file() {
+        let path = std::env::temp_dir().join(format!("ere-input-{}.bin", std::process::id()));
+        std::fs::write(&path, [4, 5, 6, 7]).unwrap();
+
+        let mut input = Input::new();
+        input.write(1u8);
+        input.write_file(&path);
+        input.write_bytes(vec![8]);
+
+        let encoded = input.encode_length_prefixed();
+        let as_bytes = input.items[1].as_bytes();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(as_bytes.unwrap(), [4, 5, 6, 7]);
+        let encoded = encoded.unwrap();
+        let mut reader = InputReader::new(&encoded);
+        assert_eq!(reader.read::<u8>().unwrap(), 1);
+        assert_eq!(reader.read_bytes().unwrap(), [4, 5, 6, 7]);
+        assert_eq!(reader.read_bytes().unwrap(), [8]);
+        assert!(reader.is_empty());
+
+        assert!(matches!(
+            input.encode_length_prefixed(),
+            Err(InputError::ReadFile { path: missing, .. }) if missing == path
+        ));
+    }
+
+    #[test]
+    fn test_to_bytes_round_trip() {
+        let path = std::env::temp_dir().join(format!("ere-stored-{}.bin", std::process::id()));
+        std::fs::write(&path, [9, 9]).unwrap();
+
+        let person = Person {
+            name: "Erin".to_string(),
+            age: 50,
+        };
+        let mut input = Input::new();
+        input.write(person.clone());
+        input.write_bytes(vec![1, 2, 3]);
+        input.write_file(&path);
+
+        let stored = input.to_bytes().unwrap();
+        let encoded = input.encode_length_prefixed();
+        std::fs::remove_file(&path).unwrap();
+
+        let replayed = Input::from_bytes(&stored).unwrap();
+        assert_eq!(replayed.len(), 3);
+        match &replayed.items[0] {
+            InputItem::Encoded(obj) => {
+                assert_eq!(obj, &EncodedObject::new(&person).unwrap());
+                assert_eq!(
+                    obj.encoded(InputEncoding::Bincode),
+                    bincode::serialize(&person).unwrap()
+                );
+
+                let mut buf = Vec::new();
+                replayed.items[0]
+                    .serialize_with(&mut bincode::Serializer::new(
+                        &mut buf,
+                        bincode::DefaultOptions::new(),
+                    ))
+                    .unwrap();
+                let written: EncodedObject =
+                    bincode::DefaultOptions::new().deserialize(&buf).unwrap();
+                assert_eq!(&written, obj);
+            }
+            item => panic!("Expected Encoded, got {item:?}"),
+        }
+        assert!(matches!(&replayed.items[2], InputItem::Bytes(bytes) if bytes == &[9, 9]));
+
+        // The replayed input hands the guest the same bytes as the original.
+        assert_eq!(replayed.encode_length_prefixed().unwrap(), encoded.unwrap());
+        // Storing the replayed input again gives the same bytes.
+        assert_eq!(replayed.to_bytes().unwrap(), stored);
+        assert_eq!(
+            Input::from_bytes(&replayed.to_bytes().unwrap())
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            stored
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut input = Input::new();
+        input.write(42u32);
+        input.write_bytes(vec![1, 2, 3]);
+
+        let json = serde_json::to_string(&input).unwrap();
+        let replayed: Input = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            replayed.encode_length_prefixed().unwrap(),
+            input.encode_length_prefixed().unwrap()
+        );
+
+        let mut missing = Input::new();
+        missing.write_file("/nonexistent/input.bin");
+        assert!(serde_json::to_string(&missing).is_err());
+        assert!(matches!(
+            missing.to_bytes(),
+            Err(InputError::ReadFile { .. })
+        ));
+    }
+
     #[test]
     fn test_iteration() {
         let mut input = Input::new();
@@ -1,15 +1,42 @@
 use std::path::Path;
 use thiserror::Error;
 
+#[cfg(feature = "capture-output")]
+mod capture;
+#[cfg(feature = "capture-output")]
+pub use capture::capture_output;
+
 mod input;
-pub use input::{Input, InputItem};
+pub use input::{
+    EncodedObject, Input, InputEncoding, InputError, InputItem, InputReader, open_input_file,
+    read_input_file,
+};
+
+mod limits;
+pub use limits::{CycleLimitExceeded, ExecutionLimits, MaxCyclesUnsupported};
 
 mod reports;
-pub use reports::{ProgramExecutionReport, ProgramProvingReport};
+pub use reports::{GuestOutput, ProgramExecutionReport, ProgramProvingReport};
 
 mod network;
 pub use network::NetworkProverConfig;
 
+mod program_digest;
+pub use program_digest::ProgramDigest;
+
+mod proof;
+pub use proof::{ProofEnvelope, ProofEnvelopeError, ProofKind};
+
+mod public_values;
+pub use public_values::{PublicValuesEncoding, PublicValuesError, PublicValuesReader};
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+/// Public values committed by the guest program, in the byte encoding of the
+/// zkVM that produced them.
+pub type PublicValues = Vec<u8>;
+
 #[allow(non_camel_case_types)]
 /// Compiler trait for compiling programs into an opaque sequence of bytes.
 pub trait Compiler {
@@ -46,7 +73,8 @@ pub enum zkVMError {
     #[error("Authentication failed: {0}")]
     Authentication(String),
 
-    /// Timeout error
+    /// Timeout error, also returned when execution runs for longer than
+    /// [`ExecutionLimits::max_duration`]
     #[error("Operation timed out after {0:?}")]
     Timeout(std::time::Duration),
 
@@ -58,8 +86,73 @@ pub enum zkVMError {
     #[error("Invalid response from prover network: {0}")]
     InvalidResponse(String),
 
-    // TODO: We can add more variants as time goes by.
-    // TODO: for now, we use this catch-all as a way to prototype faster
+    /// The guest program could not be compiled, or the compiled program could
+    /// not be set up for proving
+    #[error("Compilation failed: {0}")]
+    Compile(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The inputs could not be serialized, or do not fit what the guest accepts
+    #[error("Invalid input: {0}")]
+    InvalidInput(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The guest panicked, or exited with a non-zero exit code. Carries the
+    /// panic message and the guest output for the backends that capture them
+    #[error(
+        "Guest panicked{}: {}",
+        .exit_code.map(|code| format!(" with exit code {code}")).unwrap_or_default(),
+        .message.as_deref().map_or_else(|| .source.to_string(), str::to_string)
+    )]
+    GuestPanicked {
+        exit_code: Option<i32>,
+        message: Option<String>,
+        output: GuestOutput,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
+    /// Execution ran into the maximum number of cycles the zkVM can prove, or
+    /// into [`ExecutionLimits::max_cycles`]
+    #[error("Cycle limit exceeded: {0}")]
+    CycleLimitExceeded(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The proof was decoded but does not verify
+    #[error("Invalid proof: {0}")]
+    InvalidProof(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The proof payload could not be decoded
+    #[error("Failed to decode proof: {0}")]
+    ProofDecode(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The zkVM does not support the requested operation, e.g. a resource type
+    /// it has no prover for
+    #[error("Unsupported: {0}")]
+    Unsupported(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// Something the zkVM needs is missing, e.g. a toolchain binary, a GPU or
+    /// network credentials
+    #[error("Resource unavailable: {0}")]
+    ResourceUnavailable(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The proof is not an envelope produced by this zkVM and program
+    #[error(transparent)]
+    ProofEnvelope(#[from] ProofEnvelopeError),
+
+    /// The zkVM cannot produce the requested kind of proof, or was built
+    /// without the cargo feature it requires
+    #[error("{zkvm} does not support {proof_kind} proofs")]
+    UnsupportedProofKind {
+        zkvm: &'static str,
+        proof_kind: ProofKind,
+    },
+
+    /// The proof is valid but commits to different public values than expected
+    #[error("Public values mismatch: expected {expected:?}, got {actual:?}")]
+    PublicValuesMismatch {
+        expected: PublicValues,
+        actual: PublicValues,
+    },
+
+    /// Any other failure, e.g. the prover itself crashing
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
@@ -67,20 +160,106 @@ pub enum zkVMError {
 #[allow(non_camel_case_types)]
 #[auto_impl::auto_impl(&, Arc, Box)]
 /// zkVM trait to abstract away the differences between each zkVM
-pub trait zkVM {
-    /// Executes the given program with the inputs accumulated in the Input struct.
-    /// For RISCV programs, `program_bytes` will be the ELF binary
-    fn execute(&self, inputs: &Input) -> Result<ProgramExecutionReport, zkVMError>;
+pub trait zkVM: zkVMVerifier {
+    /// Executes the given program with the inputs accumulated in the Input struct,
+    /// returning the public values committed by the guest alongside the report.
+    ///
+    /// The public values are encoded exactly as the ones returned by `prove`, so
+    /// both can be compared byte for byte.
+    fn execute(&self, inputs: &Input) -> Result<(PublicValues, ProgramExecutionReport), zkVMError> {
+        self.execute_with_limits(inputs, &ExecutionLimits::default())
+    }
+
+    /// Same as [`zkVM::execute`], but stops the guest once it runs into `limits`
+    /// with [`zkVMError::CycleLimitExceeded`] or [`zkVMError::Timeout`].
+    fn execute_with_limits(
+        &self,
+        inputs: &Input,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, ProgramExecutionReport), zkVMError>;
+
+    /// Creates a proof of the given kind for a given program, returning the
+    /// public values committed by the guest alongside the proof.
+    ///
+    /// The proof is an encoded [`ProofEnvelope`], which `verify` checks against
+    /// the zkVM, its SDK version and the program before verifying the payload.
+    ///
+    /// Returns [`zkVMError::UnsupportedProofKind`] if the zkVM cannot produce
+    /// `proof_kind`.
+    fn prove(
+        &self,
+        inputs: &Input,
+        proof_kind: ProofKind,
+    ) -> Result<(PublicValues, Vec<u8>, ProgramProvingReport), zkVMError> {
+        self.prove_with_limits(inputs, proof_kind, &ExecutionLimits::default())
+    }
+
+    /// Same as [`zkVM::prove`], but fails with [`zkVMError::CycleLimitExceeded`]
+    /// or [`zkVMError::Timeout`] if the execution being proven runs into
+    /// `limits`, before most of the proving work is done.
+    fn prove_with_limits(
+        &self,
+        inputs: &Input,
+        proof_kind: ProofKind,
+        limits: &ExecutionLimits,
+    ) -> Result<(PublicValues, Vec<u8>, ProgramProvingReport), zkVMError>;
+
+    /// Returns the zkVM's native commitment to the program, which pins the
+    /// program its proofs belong to.
+    fn program_id(&self) -> ProgramDigest;
+
+    /// Returns the layout the guest receives the items of an [`Input`] in
+    fn input_encoding(&self) -> InputEncoding;
+}
+
+#[allow(non_camel_case_types)]
+#[auto_impl::auto_impl(&, Arc, Box)]
+/// Verification half of the [`zkVM`] trait.
+///
+/// Every zkVM implements it, and each backend also provides a verifier-only
+/// handle that implements it from the verifying key alone, without the program
+/// or the proving key.
+pub trait zkVMVerifier {
+    /// Verifies a proof for the given program and returns the public values it
+    /// commits to.
+    ///
+    /// The caller is responsible for checking that the returned public values are
+    /// the expected ones.
+    #[must_use = "public values must be checked against the expected ones"]
+    fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError>;
 
-    /// Creates a proof for a given program
-    fn prove(&self, inputs: &Input) -> Result<(Vec<u8>, ProgramProvingReport), zkVMError>;
+    /// Verifies a proof for the given program and checks that the public values
+    /// it commits to are exactly `expected`.
+    fn verify_with_public_values(&self, proof: &[u8], expected: &[u8]) -> Result<(), zkVMError> {
+        let public_values = self.verify(proof)?;
+        if public_values != expected {
+            return Err(zkVMError::PublicValuesMismatch {
+                expected: expected.to_vec(),
+                actual: public_values,
+            });
+        }
+        Ok(())
+    }
 
-    /// Verifies a proof for the given program
-    /// TODO: Pass public inputs too and check that they match if they come with the
-    /// TODO: proof, or append them if they do not.
-    /// TODO: We can also just have this return the public inputs, but then the user needs
-    /// TODO: ensure they check it for correct #[must_use]
-    fn verify(&self, proof: &[u8]) -> Result<(), zkVMError>;
+    /// Checks that verifying a proof binds it to the program, through the
+    /// program's keys or a commitment the proof carries, rather than only
+    /// through the program id the prover writes into the [`ProofEnvelope`].
+    ///
+    /// Returns [`zkVMError::Unsupported`] for zkVMs whose verifier does not
+    /// take the program, so that callers which need the program pinned can
+    /// refuse to rely on `verify` alone.
+    fn ensure_program_binding(&self) -> Result<(), zkVMError> {
+        Ok(())
+    }
+
+    /// Returns the encoding the guest-side `commit` of the zkVM uses for public values
+    fn public_values_encoding(&self) -> PublicValuesEncoding;
+
+    /// Returns a reader that decodes `public_values`, as returned by `execute`,
+    /// `prove` or `verify`, in the order and encoding they were committed with.
+    fn public_values_reader<'a>(&self, public_values: &'a [u8]) -> PublicValuesReader<'a> {
+        PublicValuesReader::new(public_values, self.public_values_encoding())
+    }
 
     /// Returns the name of the zkVM
     fn name(&self) -> &'static str;
@@ -88,3 +267,171 @@ pub trait zkVM {
     /// Returns the version of the zkVM SDK (e.g. 0.1.0)
     fn sdk_version(&self) -> &'static str;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A zkVM whose "proofs" are the public values themselves, and which rejects
+    /// empty proofs.
+    struct MockZkVM;
+
+    impl zkVM for MockZkVM {
+        fn execute_with_limits(
+            &self,
+            _inputs: &Input,
+            limits: &ExecutionLimits,
+        ) -> Result<(PublicValues, ProgramExecutionReport), zkVMError> {
+            let report = ProgramExecutionReport {
+                total_num_cycles: 42,
+                ..Default::default()
+            };
+            limits.check(report.total_num_cycles, report.execution_duration)?;
+            Ok((vec![42], report))
+        }
+
+        fn prove_with_limits(
+            &self,
+            inputs: &Input,
+            proof_kind: ProofKind,
+            limits: &ExecutionLimits,
+        ) -> Result<(PublicValues, Vec<u8>, ProgramProvingReport), zkVMError> {
+            if proof_kind != ProofKind::Core {
+                return Err(zkVMError::UnsupportedProofKind {
+                    zkvm: "mock",
+                    proof_kind,
+                });
+            }
+            self.execute_with_limits(inputs, limits)?;
+            Ok((vec![42], vec![42], ProgramProvingReport::default()))
+        }
+
+        fn program_id(&self) -> ProgramDigest {
+            ProgramDigest::new([0; 32])
+        }
+
+        fn input_encoding(&self) -> InputEncoding {
+            InputEncoding::Bincode
+        }
+    }
+
+    impl zkVMVerifier for MockZkVM {
+        fn verify(&self, proof: &[u8]) -> Result<PublicValues, zkVMError> {
+            if proof.is_empty() {
+                return Err(zkVMError::InvalidProof("empty proof".into()));
+            }
+            Ok(proof.to_vec())
+        }
+
+        fn public_values_encoding(&self) -> PublicValuesEncoding {
+            PublicValuesEncoding::Bincode
+        }
+
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn sdk_version(&self) -> &'static str {
+            "0.0.0"
+        }
+    }
+
+    #[test]
+    fn test_verify_with_public_values() {
+        let zkvm = MockZkVM;
+        let (public_values, proof, _) = zkvm.prove(&Input::new(), ProofKind::Core).unwrap();
+
+        zkvm.verify_with_public_values(&proof, &public_values)
+            .expect("proof should verify against its own public values");
+    }
+
+    #[test]
+    fn test_check_prove_verify() {
+        let (public_values, proof) =
+            test_utils::check_prove_verify(&MockZkVM, &Input::new(), ProofKind::Core);
+        assert_eq!(public_values, [42]);
+        assert_eq!(proof, [42]);
+    }
+
+    #[test]
+    fn test_ensure_program_binding() {
+        assert!(MockZkVM.ensure_program_binding().is_ok());
+    }
+
+    #[test]
+    fn test_verify_with_public_values_mismatch() {
+        let zkvm = MockZkVM;
+        let (_, proof, _) = zkvm.prove(&Input::new(), ProofKind::Core).unwrap();
+
+        let err = zkvm.verify_with_public_values(&proof, &[7]).unwrap_err();
+        assert!(matches!(
+            err,
+            zkVMError::PublicValuesMismatch { expected, actual }
+                if expected == [7] && actual == [42]
+        ));
+    }
+
+    #[test]
+    fn test_verify_with_public_values_invalid_proof() {
+        let zkvm = MockZkVM;
+
+        let err = zkvm.verify_with_public_values(&[], &[42]).unwrap_err();
+        assert!(matches!(err, zkVMError::InvalidProof(_)));
+    }
+
+    #[test]
+    fn test_guest_panicked_display() {
+        let err = zkVMError::GuestPanicked {
+            exit_code: Some(101),
+            message: None,
+            output: GuestOutput::default(),
+            source: "halted with a non-zero exit code".into(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Guest panicked with exit code 101: halted with a non-zero exit code"
+        );
+
+        let err = zkVMError::GuestPanicked {
+            exit_code: None,
+            message: Some("assertion failed".to_string()),
+            output: GuestOutput::default(),
+            source: "halted with a non-zero exit code".into(),
+        };
+        assert_eq!(err.to_string(), "Guest panicked: assertion failed");
+    }
+
+    #[test]
+    fn test_prove_with_limits() {
+        let zkvm = MockZkVM;
+
+        let limits = ExecutionLimits::new().with_max_cycles(42);
+        zkvm.prove_with_limits(&Input::new(), ProofKind::Core, &limits)
+            .expect("execution within the limits should be proven");
+
+        let limits = ExecutionLimits::new().with_max_cycles(41);
+        let err = zkvm
+            .prove_with_limits(&Input::new(), ProofKind::Core, &limits)
+            .unwrap_err();
+        assert!(matches!(err, zkVMError::CycleLimitExceeded(_)));
+        assert_eq!(
+            err.to_string(),
+            "Cycle limit exceeded: Execution exceeded the limit of 41 cycles"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_proof_kind() {
+        let zkvm = MockZkVM;
+
+        let err = zkvm.prove(&Input::new(), ProofKind::Groth16).unwrap_err();
+        assert!(matches!(
+            err,
+            zkVMError::UnsupportedProofKind {
+                proof_kind: ProofKind::Groth16,
+                ..
+            }
+        ));
+        assert_eq!(err.to_string(), "mock does not support groth16 proofs");
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    panic,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+use thiserror::Error;
+
+use crate::zkVMError;
+
+/// Bounds on a single execution, or on the execution a proof is made of.
+///
+/// `max_cycles` is in the same unit as
+/// [`ProgramExecutionReport::total_num_cycles`](crate::ProgramExecutionReport)
+/// of the backend. Backends stop the guest as soon as it runs into the limits
+/// where their SDK allows it, or check them between segments. Executors that
+/// cannot be interrupted run under [`ExecutionLimits::run_with_deadline`], and
+/// backends that cannot bound the cycles of a run reject `max_cycles` with
+/// [`zkVMError::Unsupported`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionLimits {
+    pub max_cycles: Option<u64>,
+    pub max_duration: Option<Duration>,
+}
+
+impl ExecutionLimits {
+    /// No limits, which is what `execute` and `prove` run with.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_cycles.is_none() && self.max_duration.is_none()
+    }
+
+    /// Returns [`zkVMError::CycleLimitExceeded`] or [`zkVMError::Timeout`] if a
+    /// run of `cycles` cycles that took `duration` is over the limits.
+    pub fn check(&self, cycles: u64, duration: Duration) -> Result<(), zkVMError> {
+        if let Some(max_cycles) = self.max_cycles.filter(|max_cycles| cycles > *max_cycles) {
+            return Err(zkVMError::CycleLimitExceeded(Box::new(
+                CycleLimitExceeded { max_cycles },
+            )));
+        }
+        if let Some(max_duration) = self.max_duration.filter(|max| duration > *max) {
+            return Err(zkVMError::Timeout(max_duration));
+        }
+        Ok(())
+    }
+
+    /// Runs `run` on a worker thread, returning [`zkVMError::Timeout`] if it
+    /// has not finished within `max_duration`, or runs it in place without a
+    /// `max_duration`. A panic of `run` is resumed on the calling thread.
+    ///
+    /// This is the last resort for executors that run in-process and have no
+    /// way to be stopped. The worker cannot be cancelled, so on timeout it is
+    /// detached and keeps its thread, memory and CPU until the execution
+    /// finishes on its own, which for a guest that never halts is never.
+    /// Backends that can stop their executor, between segments or by killing
+    /// a child process, do that instead.
+    pub fn run_with_deadline<T: Send + 'static>(
+        &self,
+        run: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, zkVMError> {
+        let Some(max_duration) = self.max_duration else {
+            return Ok(run());
+        };
+        let (sender, receiver) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            // The receiver is gone if the deadline passed meanwhile.
+            let _ = sender.send(run());
+        });
+        match receiver.recv_timeout(max_duration) {
+            Ok(output) => Ok(output),
+            Err(RecvTimeoutError::Timeout) => Err(zkVMError::Timeout(max_duration)),
+            Err(RecvTimeoutError::Disconnected) => match worker.join() {
+                Err(payload) => panic::resume_unwind(payload),
+                Ok(()) => unreachable!("the worker sends its output before exiting"),
+            },
+        }
+    }
+}
+
+/// Source of the [`zkVMError::Unsupported`] returned when a backend whose
+/// executor cannot be stopped after a number of cycles is given
+/// [`ExecutionLimits::max_cycles`].
+#[derive(Debug, Error)]
+#[error("{zkvm} cannot bound the execution by a number of cycles")]
+pub struct MaxCyclesUnsupported {
+    pub zkvm: &'static str,
+}
+
+/// Source of the [`zkVMError::CycleLimitExceeded`] returned when the guest runs
+/// for more than [`ExecutionLimits::max_cycles`].
+#[derive(Debug, Error)]
+#[error("Execution exceeded the limit of {max_cycles} cycles")]
+pub struct CycleLimitExceeded {
+    pub max_cycles: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{panic, thread, time::Duration};
+
+    use super::ExecutionLimits;
+    use crate::zkVMError;
+
+    #[test]
+    fn test_check() {
+        let limits = ExecutionLimits::new()
+            .with_max_cycles(100)
+            .with_max_duration(Duration::from_secs(1));
+
+        assert!(limits.check(100, Duration::from_secs(1)).is_ok());
+        assert!(matches!(
+            limits.check(101, Duration::ZERO),
+            Err(zkVMError::CycleLimitExceeded(_))
+        ));
+        assert!(matches!(
+            limits.check(0, Duration::from_secs(2)),
+            Err(zkVMError::Timeout(max_duration)) if max_duration == Duration::from_secs(1)
+        ));
+
+        assert!(ExecutionLimits::new().is_unlimited());
+        assert!(
+            ExecutionLimits::new()
+                .check(u64::MAX, Duration::MAX)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_run_with_deadline() {
+        let limits = ExecutionLimits::new().with_max_duration(Duration::from_millis(50));
+        assert_eq!(limits.run_with_deadline(|| 42).unwrap(), 42);
+        assert!(matches!(
+            limits.run_with_deadline(|| thread::sleep(Duration::from_secs(1))),
+            Err(zkVMError::Timeout(_))
+        ));
+
+        let panicked = panic::catch_unwind(|| limits.run_with_deadline(|| panic!("guest failed")));
+        assert_eq!(
+            panicked.unwrap_err().downcast_ref::<&str>(),
+            Some(&"guest failed")
+        );
+
+        assert_eq!(ExecutionLimits::new().run_with_deadline(|| 7).unwrap(), 7);
+    }
+}
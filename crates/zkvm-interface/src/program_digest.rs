@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Commitment to a guest program, which pins the program a proof belongs to.
+///
+/// Each zkVM uses its own native commitment, so digests are only comparable
+/// between programs of the same zkVM:
+/// - SP1: hash of the verifying key.
+/// - RISC Zero: image ID.
+/// - OpenVM: commitment to the transpiled exe, its initial memory and entry point.
+/// - Jolt, Pico, ZisK: blake3 hash of the ELF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ProgramDigest([u8; 32]);
+
+impl ProgramDigest {
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for ProgramDigest {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ProgramDigest> for [u8; 32] {
+    fn from(digest: ProgramDigest) -> Self {
+        digest.0
+    }
+}
+
+impl AsRef<[u8]> for ProgramDigest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Formats the digest as lowercase hex, without a `0x` prefix.
+impl fmt::Display for ProgramDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_hex() {
+        let mut bytes = [0; 32];
+        bytes[0] = 0xab;
+        bytes[31] = 0x01;
+
+        let hex = ProgramDigest::new(bytes).to_string();
+        assert_eq!(hex.len(), 64);
+        assert!(hex.starts_with("ab00"));
+        assert!(hex.ends_with("0001"));
+    }
+}
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+use crate::ProgramDigest;
+
+/// Magic bytes every encoded [`ProofEnvelope`] starts with.
+const MAGIC: [u8; 4] = *b"EREP";
+
+/// Version of the envelope layout, bumped whenever the encoding changes.
+const VERSION: u16 = 1;
+
+/// The kind of proof a zkVM produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProofKind {
+    /// Proof of the execution itself, e.g. one STARK proof per shard or segment.
+    Core,
+    /// Core proofs recursively aggregated into a single constant-size STARK proof.
+    Compressed,
+    /// Compressed proof wrapped into a Groth16 SNARK.
+    Groth16,
+    /// Compressed proof wrapped into a PLONK SNARK.
+    Plonk,
+    /// Compressed proof wrapped into a Halo2 SNARK, e.g. OpenVM's EVM proof.
+    Halo2,
+}
+
+impl fmt::Display for ProofKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProofKind::Core => "core",
+            ProofKind::Compressed => "compressed",
+            ProofKind::Groth16 => "groth16",
+            ProofKind::Plonk => "plonk",
+            ProofKind::Halo2 => "halo2",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Self-describing container for the proofs returned by `zkVM::prove`.
+///
+/// The backend-specific proof encoding is kept as an opaque `payload`, next to
+/// enough metadata to reject a proof handed to the wrong backend before trying
+/// to decode it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofEnvelope {
+    /// Name of the zkVM that produced the proof.
+    pub zkvm_name: String,
+    /// Version of the zkVM SDK that produced the proof.
+    pub sdk_version: String,
+    /// Commitment to the program the proof belongs to.
+    pub program_id: ProgramDigest,
+    /// The kind of proof in `payload`.
+    pub proof_kind: ProofKind,
+    /// The proof, in the backend-specific encoding.
+    pub payload: Vec<u8>,
+}
+
+/// Errors from decoding a [`ProofEnvelope`].
+#[derive(Debug, Error)]
+pub enum ProofEnvelopeError {
+    #[error("Not a proof envelope: missing magic header")]
+    InvalidMagic,
+
+    #[error("Unsupported proof envelope version {0}, expected {VERSION}")]
+    UnsupportedVersion(u16),
+
+    #[error("Malformed proof envelope: {0}")]
+    Malformed(#[from] bincode::Error),
+
+    #[error("Proof was produced by {actual}, but is being verified by {expected}")]
+    ZkvmMismatch { expected: String, actual: String },
+
+    #[error("Proof was produced by SDK version {actual}, but the verifier uses {expected}")]
+    SdkVersionMismatch { expected: String, actual: String },
+
+    #[error("Proof belongs to program {actual}, expected {expected}")]
+    ProgramMismatch {
+        expected: ProgramDigest,
+        actual: ProgramDigest,
+    },
+}
+
+impl ProofEnvelope {
+    pub fn new(
+        zkvm_name: impl Into<String>,
+        sdk_version: impl Into<String>,
+        program_id: ProgramDigest,
+        proof_kind: ProofKind,
+        payload: Vec<u8>,
+    ) -> Self {
+        Self {
+            zkvm_name: zkvm_name.into(),
+            sdk_version: sdk_version.into(),
+            program_id,
+            proof_kind,
+            payload,
+        }
+    }
+
+    /// Encodes the envelope as the magic header and version, followed by the
+    /// `bincode`-encoded fields.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 2 + self.payload.len() + 128);
+        bytes.extend(MAGIC);
+        bytes.extend(VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, self).expect("serializing into a Vec cannot fail");
+        bytes
+    }
+
+    /// Decodes an envelope, rejecting it unless it was produced by the zkVM
+    /// `zkvm_name` at SDK version `sdk_version`.
+    pub fn decode(
+        bytes: &[u8],
+        zkvm_name: &str,
+        sdk_version: &str,
+    ) -> Result<Self, ProofEnvelopeError> {
+        let envelope = Self::inspect(bytes)?;
+        if envelope.zkvm_name != zkvm_name {
+            return Err(ProofEnvelopeError::ZkvmMismatch {
+                expected: zkvm_name.to_string(),
+                actual: envelope.zkvm_name,
+            });
+        }
+        if envelope.sdk_version != sdk_version {
+            return Err(ProofEnvelopeError::SdkVersionMismatch {
+                expected: sdk_version.to_string(),
+                actual: envelope.sdk_version,
+            });
+        }
+        Ok(envelope)
+    }
+
+    /// Decodes an envelope without checking which zkVM produced it, e.g. to
+    /// find out which backend a stored proof has to be routed to.
+    pub fn inspect(bytes: &[u8]) -> Result<Self, ProofEnvelopeError> {
+        let rest = bytes
+            .strip_prefix(&MAGIC)
+            .ok_or(ProofEnvelopeError::InvalidMagic)?;
+        let (version, body) = rest
+            .split_first_chunk::<2>()
+            .ok_or(ProofEnvelopeError::InvalidMagic)?;
+        let version = u16::from_le_bytes(*version);
+        if version != VERSION {
+            return Err(ProofEnvelopeError::UnsupportedVersion(version));
+        }
+        Ok(bincode::deserialize(body)?)
+    }
+
+    /// Checks that the proof belongs to the program committed to by `expected`.
+    pub fn ensure_program(&self, expected: &ProgramDigest) -> Result<(), ProofEnvelopeError> {
+        if self.program_id != *expected {
+            return Err(ProofEnvelopeError::ProgramMismatch {
+                expected: *expected,
+                actual: self.program_id,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> ProofEnvelope {
+        ProofEnvelope::new(
+            "sp1",
+            "5.0.5",
+            ProgramDigest::new([7; 32]),
+            ProofKind::Compressed,
+            vec![1, 2, 3],
+        )
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let envelope = envelope();
+        let decoded = ProofEnvelope::decode(&envelope.encode(), "sp1", "5.0.5").unwrap();
+        assert_eq!(decoded, envelope);
+    }
+
+    #[test]
+    fn test_decode_rejects_other_zkvm() {
+        let err = ProofEnvelope::decode(&envelope().encode(), "zisk", "5.0.5").unwrap_err();
+        assert!(matches!(
+            err,
+            ProofEnvelopeError::ZkvmMismatch { expected, actual }
+                if expected == "zisk" && actual == "sp1"
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_other_sdk_version() {
+        let err = ProofEnvelope::decode(&envelope().encode(), "sp1", "4.0.0").unwrap_err();
+        assert!(matches!(err, ProofEnvelopeError::SdkVersionMismatch { .. }));
+    }
+
+    #[test]
+    fn test_decode_rejects_bare_proof() {
+        let err = ProofEnvelope::inspect(&[1, 2, 3]).unwrap_err();
+        assert!(matches!(err, ProofEnvelopeError::InvalidMagic));
+
+        let mut bytes = envelope().encode();
+        bytes[MAGIC.len()] += 1;
+        let err = ProofEnvelope::inspect(&bytes).unwrap_err();
+        assert!(matches!(err, ProofEnvelopeError::UnsupportedVersion(2)));
+
+        let bytes = envelope().encode();
+        let err = ProofEnvelope::inspect(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(matches!(err, ProofEnvelopeError::Malformed(_)));
+    }
+
+    #[test]
+    fn test_ensure_program() {
+        let envelope = envelope();
+        envelope
+            .ensure_program(&ProgramDigest::new([7; 32]))
+            .unwrap();
+        assert!(matches!(
+            envelope.ensure_program(&ProgramDigest::new([0; 32])),
+            Err(ProofEnvelopeError::ProgramMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,995 @@
+use bincode::Options;
+use serde::{
+    Deserializer as _, Serialize,
+    de::{self, DeserializeOwned, DeserializeSeed, Error as _, IntoDeserializer, Visitor},
+    ser,
+};
+use thiserror::Error;
+
+/// Encoding used by the guest-side `commit` of a zkVM to write public values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicValuesEncoding {
+    /// Values are concatenated `bincode` encodings with fixed-width little-endian
+    /// integers, as written by `sp1_zkvm::io::commit` and `pico_sdk::io::commit`.
+    Bincode,
+    /// Values are `bincode` encodings as with [`Bincode`](Self::Bincode), each
+    /// padded with zeros to a multiple of 4 bytes, as committed by
+    /// `ere_guest::commit` on OpenVM and ZisK, which write public values a `u32`
+    /// at a time.
+    ///
+    /// This also decodes the `u32`s revealed with `openvm::io::reveal_u32` or
+    /// written to the output slots with `ziskos::set_output`.
+    WordAlignedBincode,
+    /// Values are laid out over little-endian `u32` words following the
+    /// `risc0_zkvm::serde` format, as written by `env::commit`.
+    Words,
+    /// Values are concatenated `postcard` encodings, as written for the return
+    /// value of a `#[jolt::provable]` function.
+    Postcard,
+}
+
+#[derive(Debug, Error)]
+pub enum PublicValuesError {
+    #[error("Unexpected end of public values")]
+    UnexpectedEnd,
+
+    #[error("Failed to decode public values: {0}")]
+    Message(String),
+
+    #[error("Failed to decode public values with `bincode`: {0}")]
+    Bincode(#[from] bincode::Error),
+
+    #[error("Failed to decode public values with `postcard`: {0}")]
+    Postcard(#[from] postcard::Error),
+}
+
+impl de::Error for PublicValuesError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        PublicValuesError::Message(msg.to_string())
+    }
+}
+
+impl ser::Error for PublicValuesError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        PublicValuesError::Message(msg.to_string())
+    }
+}
+
+impl PublicValuesEncoding {
+    /// Encode `value` the way the guest-side `commit` of the zkVM does, so that
+    /// [`PublicValuesReader::read`] decodes it back.
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, PublicValuesError> {
+        match self {
+            PublicValuesEncoding::Bincode => Ok(bincode::DefaultOptions::new()
+                .with_fixint_encoding()
+                .serialize(value)?),
+            PublicValuesEncoding::WordAlignedBincode => {
+                let mut bytes = PublicValuesEncoding::Bincode.encode(value)?;
+                bytes.resize(bytes.len().next_multiple_of(4), 0);
+                Ok(bytes)
+            }
+            PublicValuesEncoding::Words => {
+                let mut serializer = Serializer::new();
+                value.serialize(&mut serializer)?;
+                Ok(serializer.output)
+            }
+            PublicValuesEncoding::Postcard => Ok(postcard::to_allocvec(value)?),
+        }
+    }
+}
+
+/// Cursor over the public values committed by a guest, decoding them in the
+/// same order and encoding they were committed with. This mirrors
+/// [`Input::write`](crate::Input::write) on the output side.
+#[derive(Debug, Clone)]
+pub struct PublicValuesReader<'a> {
+    public_values: &'a [u8],
+    encoding: PublicValuesEncoding,
+}
+
+impl<'a> PublicValuesReader<'a> {
+    pub fn new(public_values: &'a [u8], encoding: PublicValuesEncoding) -> Self {
+        Self {
+            public_values,
+            encoding,
+        }
+    }
+
+    /// Read the next committed value
+    pub fn read<T: DeserializeOwned>(&mut self) -> Result<T, PublicValuesError> {
+        match self.encoding {
+            PublicValuesEncoding::Bincode => read_bincode(&mut self.public_values),
+            PublicValuesEncoding::WordAlignedBincode => {
+                let len = self.public_values.len();
+                let value = read_bincode(&mut self.public_values)?;
+                let read = len - self.public_values.len();
+                take(&mut self.public_values, read.next_multiple_of(4) - read)?;
+                Ok(value)
+            }
+            PublicValuesEncoding::Words => {
+                T::deserialize(&mut Deserializer::new(&mut self.public_values))
+            }
+            PublicValuesEncoding::Postcard => {
+                let (value, rest) = postcard::take_from_bytes(self.public_values)?;
+                self.public_values = rest;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Get the bytes that have not been read yet
+    pub fn remaining(&self) -> &'a [u8] {
+        self.public_values
+    }
+
+    /// Check if every committed value has been read
+    pub fn is_empty(&self) -> bool {
+        self.public_values.is_empty()
+    }
+}
+
+fn read_bincode<T: DeserializeOwned>(input: &mut &[u8]) -> Result<T, PublicValuesError> {
+    Ok(bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .deserialize_from(input)?)
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], PublicValuesError> {
+    if input.len() < len {
+        return Err(PublicValuesError::UnexpectedEnd);
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Ok(head)
+}
+
+fn take_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], PublicValuesError> {
+    Ok(take(input, N)?.try_into().expect("slice has length N"))
+}
+
+/// The `risc0_zkvm::serde` format, where every value is made of `u32` words.
+struct Words;
+
+impl Words {
+    fn bool(input: &mut &[u8]) -> Result<bool, PublicValuesError> {
+        Self::u32(input).map(|word| word != 0)
+    }
+
+    fn u32(input: &mut &[u8]) -> Result<u32, PublicValuesError> {
+        take_array(input).map(u32::from_le_bytes)
+    }
+
+    fn u64(input: &mut &[u8]) -> Result<u64, PublicValuesError> {
+        let low = Self::u32(input)? as u64;
+        let high = Self::u32(input)? as u64;
+        Ok(low | (high << 32))
+    }
+
+    fn u128(input: &mut &[u8]) -> Result<u128, PublicValuesError> {
+        let low = Self::u64(input)? as u128;
+        let high = Self::u64(input)? as u128;
+        Ok(low | (high << 64))
+    }
+
+    fn i32(input: &mut &[u8]) -> Result<i32, PublicValuesError> {
+        Self::u32(input).map(|word| word as i32)
+    }
+
+    fn i64(input: &mut &[u8]) -> Result<i64, PublicValuesError> {
+        Self::u64(input).map(|value| value as i64)
+    }
+
+    fn i128(input: &mut &[u8]) -> Result<i128, PublicValuesError> {
+        Self::u128(input).map(|value| value as i128)
+    }
+
+    fn u8(input: &mut &[u8]) -> Result<u8, PublicValuesError> {
+        Self::u32(input).map(|word| word as u8)
+    }
+
+    fn u16(input: &mut &[u8]) -> Result<u16, PublicValuesError> {
+        Self::u32(input).map(|word| word as u16)
+    }
+
+    fn i8(input: &mut &[u8]) -> Result<i8, PublicValuesError> {
+        Self::u32(input).map(|word| word as i8)
+    }
+
+    fn i16(input: &mut &[u8]) -> Result<i16, PublicValuesError> {
+        Self::u32(input).map(|word| word as i16)
+    }
+
+    fn char(input: &mut &[u8]) -> Result<char, PublicValuesError> {
+        let word = Self::u32(input)?;
+        char::from_u32(word).ok_or_else(|| de::Error::custom(format!("invalid char {word:#x}")))
+    }
+
+    fn f32(input: &mut &[u8]) -> Result<f32, PublicValuesError> {
+        Self::u32(input).map(f32::from_bits)
+    }
+
+    fn f64(input: &mut &[u8]) -> Result<f64, PublicValuesError> {
+        Self::u64(input).map(f64::from_bits)
+    }
+
+    /// Length prefix of strings, byte arrays, sequences and maps.
+    fn len(input: &mut &[u8]) -> Result<usize, PublicValuesError> {
+        Self::u32(input).map(|word| word as usize)
+    }
+
+    /// Content of a string or byte array of `len` bytes.
+    fn bytes<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], PublicValuesError> {
+        // Bytes are packed into words, padding the last one with zeros.
+        let padded = take(input, len.div_ceil(4) * 4)?;
+        Ok(&padded[..len])
+    }
+
+    fn write_bool(output: &mut Vec<u8>, value: bool) {
+        Self::write_u32(output, value as u32)
+    }
+
+    fn write_u32(output: &mut Vec<u8>, value: u32) {
+        output.extend(value.to_le_bytes())
+    }
+
+    fn write_u64(output: &mut Vec<u8>, value: u64) {
+        Self::write_u32(output, value as u32);
+        Self::write_u32(output, (value >> 32) as u32);
+    }
+
+    fn write_u128(output: &mut Vec<u8>, value: u128) {
+        Self::write_u64(output, value as u64);
+        Self::write_u64(output, (value >> 64) as u64);
+    }
+
+    fn write_i32(output: &mut Vec<u8>, value: i32) {
+        Self::write_u32(output, value as u32)
+    }
+
+    fn write_i64(output: &mut Vec<u8>, value: i64) {
+        Self::write_u64(output, value as u64)
+    }
+
+    fn write_i128(output: &mut Vec<u8>, value: i128) {
+        Self::write_u128(output, value as u128)
+    }
+
+    fn write_u8(output: &mut Vec<u8>, value: u8) {
+        Self::write_u32(output, value as u32)
+    }
+
+    fn write_u16(output: &mut Vec<u8>, value: u16) {
+        Self::write_u32(output, value as u32)
+    }
+
+    fn write_i8(output: &mut Vec<u8>, value: i8) {
+        Self::write_i32(output, value as i32)
+    }
+
+    fn write_i16(output: &mut Vec<u8>, value: i16) {
+        Self::write_i32(output, value as i32)
+    }
+
+    fn write_char(output: &mut Vec<u8>, value: char) {
+        Self::write_u32(output, value as u32)
+    }
+
+    fn write_f32(output: &mut Vec<u8>, value: f32) {
+        Self::write_u32(output, value.to_bits())
+    }
+
+    fn write_f64(output: &mut Vec<u8>, value: f64) {
+        Self::write_u64(output, value.to_bits())
+    }
+
+    fn write_len(output: &mut Vec<u8>, len: usize) -> Result<(), PublicValuesError> {
+        let len = u32::try_from(len).map_err(<PublicValuesError as ser::Error>::custom)?;
+        Self::write_u32(output, len);
+        Ok(())
+    }
+
+    fn write_bytes(output: &mut Vec<u8>, bytes: &[u8]) {
+        output.extend(bytes);
+        output.resize(output.len().div_ceil(4) * 4, 0);
+    }
+}
+
+/// A serde deserializer for the [`Words`] format, advancing the given cursor
+/// as values are read.
+struct Deserializer<'a, 'b> {
+    input: &'b mut &'a [u8],
+}
+
+impl<'a, 'b> Deserializer<'a, 'b> {
+    fn new(input: &'b mut &'a [u8]) -> Self {
+        Self { input }
+    }
+
+    fn str(&mut self) -> Result<&'a str, PublicValuesError> {
+        let bytes = self.bytes()?;
+        std::str::from_utf8(bytes).map_err(PublicValuesError::custom)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], PublicValuesError> {
+        let len = Words::len(self.input)?;
+        Words::bytes(self.input, len)
+    }
+}
+
+macro_rules! deserialize_primitive {
+    ($($method:ident => $visit:ident($read:ident)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(Words::$read(self.input)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de, '_> {
+    type Error = PublicValuesError;
+
+    deserialize_primitive! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+        deserialize_char => visit_char(char),
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom(
+            "public values are not self-describing, a concrete type is required",
+        ))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_bytes(self.bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if Words::bool(self.input)? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let len = Words::len(self.input)?;
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let len = Words::len(self.input)?;
+        visitor.visit_map(Access { de: self, len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u32(Words::u32(self.input)?)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom(
+            "public values are not self-describing, values cannot be skipped",
+        ))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Access to the `len` elements of a sequence, tuple, struct or map.
+struct Access<'a, 'b, 'c> {
+    de: &'c mut Deserializer<'a, 'b>,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'de, '_, '_> {
+    type Error = PublicValuesError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'de, '_, '_> {
+    type Error = PublicValuesError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de, '_> {
+    type Error = PublicValuesError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let index = Words::u32(self.input)?;
+        let value = seed.deserialize(IntoDeserializer::<PublicValuesError>::into_deserializer(
+            index,
+        ))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de, '_> {
+    type Error = PublicValuesError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+}
+
+/// A serde serializer for the [`Words`] format, the inverse of
+/// [`Deserializer`].
+struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    fn new() -> Self {
+        Self { output: Vec::new() }
+    }
+
+    fn len(&mut self, len: Option<usize>) -> Result<(), PublicValuesError> {
+        let len = len.ok_or_else(|| {
+            <PublicValuesError as ser::Error>::custom(
+                "sequences and maps must know their length to be committed",
+            )
+        })?;
+        Words::write_len(&mut self.output, len)
+    }
+}
+
+macro_rules! serialize_primitive {
+    ($($method:ident($ty:ty) => $write:ident),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Self::Ok, Self::Error> {
+                Words::$write(&mut self.output, value);
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = ();
+    type Error = PublicValuesError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_primitive! {
+        serialize_bool(bool) => write_bool,
+        serialize_u8(u8) => write_u8,
+        serialize_u16(u16) => write_u16,
+        serialize_u32(u32) => write_u32,
+        serialize_u64(u64) => write_u64,
+        serialize_u128(u128) => write_u128,
+        serialize_i8(i8) => write_i8,
+        serialize_i16(i16) => write_i16,
+        serialize_i32(i32) => write_i32,
+        serialize_i64(i64) => write_i64,
+        serialize_i128(i128) => write_i128,
+        serialize_f32(f32) => write_f32,
+        serialize_f64(f64) => write_f64,
+        serialize_char(char) => write_char,
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(value.as_bytes())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Words::write_len(&mut self.output, value.len())?;
+        Words::write_bytes(&mut self.output, value);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Words::write_bool(&mut self.output, false);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        Words::write_bool(&mut self.output, true);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Words::write_u32(&mut self.output, variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Words::write_u32(&mut self.output, variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Words::write_u32(&mut self.output, variant_index);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Words::write_u32(&mut self.output, variant_index);
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! serialize_compound {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(
+            impl ser::$trait for &mut Serializer {
+                type Ok = ();
+                type Error = PublicValuesError;
+
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), Self::Error> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+serialize_compound! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+impl ser::SerializeMap for &mut Serializer {
+    type Ok = ();
+    type Error = PublicValuesError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Serializer {
+    type Ok = ();
+    type Error = PublicValuesError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Serializer {
+    type Ok = ();
+    type Error = PublicValuesError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Block {
+        number: u64,
+        hash: [u8; 4],
+        valid: bool,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    enum Status {
+        Ok,
+        Failed(u16),
+    }
+
+    fn words(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_read_bincode() {
+        let block = Block {
+            number: 1,
+            hash: [1, 2, 3, 4],
+            valid: true,
+        };
+        let mut public_values = bincode::serialize(&42u32).unwrap();
+        public_values.extend(bincode::serialize(&block).unwrap());
+        public_values.extend(bincode::serialize(&Status::Failed(7)).unwrap());
+
+        let mut reader = PublicValuesReader::new(&public_values, PublicValuesEncoding::Bincode);
+        assert_eq!(reader.read::<u32>().unwrap(), 42);
+        assert_eq!(reader.read::<Block>().unwrap(), block);
+        assert_eq!(reader.read::<Status>().unwrap(), Status::Failed(7));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_words() {
+        let mut public_values = words(&[42, 1, 0, 1, 2, 3, 4, 1, 1, 7]);
+        // "abcde" is packed into two words, the last one padded with zeros.
+        public_values.extend(words(&[5]));
+        public_values.extend(b"abcde\0\0\0");
+        public_values.extend(words(&[0xffff_fffe]));
+
+        let mut reader = PublicValuesReader::new(&public_values, PublicValuesEncoding::Words);
+        assert_eq!(reader.read::<u8>().unwrap(), 42);
+        assert_eq!(
+            reader.read::<Block>().unwrap(),
+            Block {
+                number: 1,
+                hash: [1, 2, 3, 4],
+                valid: true,
+            }
+        );
+        assert_eq!(reader.read::<Status>().unwrap(), Status::Failed(7));
+        assert_eq!(reader.read::<String>().unwrap(), "abcde");
+        assert_eq!(reader.read::<i32>().unwrap(), -2);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_words_u32_slots() {
+        // Two `u32` output slots hold a single `u64`.
+        let public_values = words(&[0x89ab_cdef, 0x0123_4567]);
+
+        let mut reader = PublicValuesReader::new(&public_values, PublicValuesEncoding::Words);
+        assert_eq!(reader.read::<u64>().unwrap(), 0x0123_4567_89ab_cdef);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_word_aligned_bincode() {
+        // Values start on a word boundary, with the padding of `7u8` skipped.
+        let mut public_values = words(&[7, 0x89ab_cdef, 0x0123_4567]);
+        public_values.extend(b"\x03\0\0\0\0\0\0\0abc\0");
+
+        let mut reader =
+            PublicValuesReader::new(&public_values, PublicValuesEncoding::WordAlignedBincode);
+        assert_eq!(reader.read::<u8>().unwrap(), 7);
+        assert_eq!(reader.read::<u64>().unwrap(), 0x0123_4567_89ab_cdef);
+        assert_eq!(reader.read::<String>().unwrap(), "abc");
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_postcard() {
+        let public_values = [
+            0xac, 0x02, // 300u128 as a varint
+            0x03, // -2i32 zigzagged
+            0x01, 0x05, // Some(5u64)
+            0x02, 0x68, 0x69, // "hi"
+            0x01, 0x07, // Status::Failed(7)
+        ];
+
+        let mut reader = PublicValuesReader::new(&public_values, PublicValuesEncoding::Postcard);
+        assert_eq!(reader.read::<u128>().unwrap(), 300);
+        assert_eq!(reader.read::<i32>().unwrap(), -2);
+        assert_eq!(reader.read::<Option<u64>>().unwrap(), Some(5));
+        assert_eq!(reader.read::<String>().unwrap(), "hi");
+        assert_eq!(reader.read::<Status>().unwrap(), Status::Failed(7));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_encode_matches_read() {
+        let block = Block {
+            number: u64::MAX - 1,
+            hash: [1, 2, 3, 4],
+            valid: true,
+        };
+
+        for encoding in [
+            PublicValuesEncoding::Bincode,
+            PublicValuesEncoding::WordAlignedBincode,
+            PublicValuesEncoding::Words,
+            PublicValuesEncoding::Postcard,
+        ] {
+            let mut public_values = encoding.encode(&42u8).unwrap();
+            public_values.extend(encoding.encode(&block).unwrap());
+            public_values.extend(encoding.encode(&Status::Failed(7)).unwrap());
+            public_values.extend(encoding.encode("abcde").unwrap());
+            public_values.extend(encoding.encode(&Some(-2i64)).unwrap());
+            public_values.extend(encoding.encode(&vec!['x', 'é']).unwrap());
+
+            let mut reader = PublicValuesReader::new(&public_values, encoding);
+            assert_eq!(reader.read::<u8>().unwrap(), 42, "{encoding:?}");
+            assert_eq!(reader.read::<Block>().unwrap(), block, "{encoding:?}");
+            assert_eq!(reader.read::<Status>().unwrap(), Status::Failed(7));
+            assert_eq!(reader.read::<String>().unwrap(), "abcde", "{encoding:?}");
+            assert_eq!(reader.read::<Option<i64>>().unwrap(), Some(-2));
+            assert_eq!(reader.read::<Vec<char>>().unwrap(), ['x', 'é']);
+            assert!(reader.is_empty(), "{encoding:?}");
+        }
+    }
+
+    #[test]
+    fn test_encode_words() {
+        let mut expected = words(&[42, 1, 0, 1, 2, 3, 4, 1, 1, 7, 5]);
+        expected.extend(b"abcde\0\0\0");
+        expected.extend(words(&[0xffff_fffe]));
+
+        let block = Block {
+            number: 1,
+            hash: [1, 2, 3, 4],
+            valid: true,
+        };
+        let encoded = PublicValuesEncoding::Words
+            .encode(&(42u8, block, Status::Failed(7), "abcde", -2i32))
+            .unwrap();
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn test_encode_postcard() {
+        let encoded = PublicValuesEncoding::Postcard
+            .encode(&(300u128, -2i32, Some(5u64), "hi", Status::Failed(7)))
+            .unwrap();
+        assert_eq!(
+            encoded,
+            [0xac, 0x02, 0x03, 0x01, 0x05, 0x02, 0x68, 0x69, 0x01, 0x07]
+        );
+    }
+
+    #[test]
+    fn test_read_past_end() {
+        let public_values = words(&[42]);
+
+        let mut reader = PublicValuesReader::new(&public_values, PublicValuesEncoding::Words);
+        assert!(matches!(
+            reader.read::<u64>(),
+            Err(PublicValuesError::UnexpectedEnd)
+        ));
+
+        let mut reader = PublicValuesReader::new(&public_values, PublicValuesEncoding::Bincode);
+        assert!(matches!(
+            reader.read::<u64>(),
+            Err(PublicValuesError::Bincode(_))
+        ));
+    }
+}
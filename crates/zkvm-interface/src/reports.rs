@@ -12,6 +12,8 @@ pub struct ProgramExecutionReport {
     pub region_cycles: IndexMap<String, u64>,
     /// Execution duration.
     pub execution_duration: Duration,
+    /// What the guest wrote to stdout and stderr, for backends that capture it.
+    pub guest_output: GuestOutput,
 }
 
 impl ProgramExecutionReport {
@@ -26,6 +28,35 @@ impl ProgramExecutionReport {
     }
 }
 
+/// Output a guest program wrote to stdout and stderr while running.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl GuestOutput {
+    /// Returns the last line of stderr that reports a Rust panic, i.e. the
+    /// `panicked at <location>:` line and the message following it.
+    ///
+    /// Falls back to stdout, for executors that print both streams of the guest
+    /// there.
+    pub fn panic_message(&self) -> Option<String> {
+        Self::find_panic(&self.stderr).or_else(|| Self::find_panic(&self.stdout))
+    }
+
+    fn find_panic(stream: &[u8]) -> Option<String> {
+        let stream = String::from_utf8_lossy(stream);
+        let (_, panic) = stream.rsplit_once("panicked at ")?;
+        let mut lines = panic.lines();
+        let location = lines.next()?.trim_end_matches(':');
+        match lines.next() {
+            Some(message) => Some(format!("{message} at {location}")),
+            None => Some(location.to_string()),
+        }
+    }
+}
+
 /// ProgramProvingReport produces information about proving a particular
 /// program's instance.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -37,3 +68,31 @@ impl ProgramProvingReport {
         Self { proving_time }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GuestOutput;
+
+    #[test]
+    fn test_panic_message() {
+        let output = GuestOutput {
+            stdout: b"fib(10) = 55\n".to_vec(),
+            stderr: b"thread 'main' panicked at src/main.rs:7:5:\nn must be positive\n".to_vec(),
+        };
+        assert_eq!(
+            output.panic_message().as_deref(),
+            Some("n must be positive at src/main.rs:7:5")
+        );
+
+        let output = GuestOutput {
+            stdout: b"panicked at src/main.rs:7:5:\nn must be positive\n".to_vec(),
+            stderr: Vec::new(),
+        };
+        assert_eq!(
+            output.panic_message().as_deref(),
+            Some("n must be positive at src/main.rs:7:5")
+        );
+
+        assert_eq!(GuestOutput::default().panic_message(), None);
+    }
+}
@@ -0,0 +1,291 @@
+//! Checks shared by the tests of the backends, enabled with the `test-utils`
+//! feature.
+
+use std::{collections::BTreeMap, fmt::Debug};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    Input, InputEncoding, InputItem, InputReader, ProofKind, PublicValues, PublicValuesEncoding,
+    zkVM, zkVMError,
+};
+
+/// Reads the items of an [`Input`] back the way a guest does, from what the
+/// stdin builder of a backend hands to it.
+pub trait GuestInputReader {
+    /// Reads an item written with [`Input::write`].
+    fn read<T: DeserializeOwned>(&mut self) -> T;
+
+    /// Reads an item written with [`Input::write_bytes`] or
+    /// [`Input::write_file`].
+    fn read_bytes(&mut self) -> Vec<u8>;
+
+    /// Whether every item has been read.
+    fn is_empty(&self) -> bool;
+}
+
+/// Guest side of [`InputEncoding::LengthPrefixedBincode`], reading the stream
+/// written by [`Input::write_length_prefixed`] with [`InputReader`].
+pub struct LengthPrefixedInput {
+    stream: Vec<u8>,
+    offset: usize,
+}
+
+impl LengthPrefixedInput {
+    pub fn new(stream: Vec<u8>) -> Self {
+        Self { stream, offset: 0 }
+    }
+
+    fn reader(&self) -> InputReader<'_> {
+        InputReader::new(&self.stream[self.offset..])
+    }
+}
+
+impl GuestInputReader for LengthPrefixedInput {
+    fn read<T: DeserializeOwned>(&mut self) -> T {
+        let value = self.reader().read().unwrap();
+        self.read_bytes();
+        value
+    }
+
+    fn read_bytes(&mut self) -> Vec<u8> {
+        let bytes = self.reader().read_bytes().unwrap().to_vec();
+        self.offset += 4 + bytes.len();
+        bytes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.reader().is_empty()
+    }
+}
+
+/// Checks that a guest reads back the items of an [`Input`] as they were
+/// written, for a corpus of values written as objects, as objects replayed
+/// with [`Input::from_bytes`], between raw bytes items of odd lengths, and for
+/// a file item.
+///
+/// `guest_input` runs the stdin builder of the backend, and returns a reader
+/// decoding its output with the same code as the guest, so that a backend
+/// framing an item differently than its guest reads it shows up as a mismatch
+/// without proving anything. `encoding` is the one the backend returns from
+/// `zkVM::input_encoding`.
+pub fn check_input_parity<R: GuestInputReader>(
+    encoding: InputEncoding,
+    guest_input: impl Fn(&Input) -> R,
+) {
+    let check = Parity {
+        encoding,
+        guest_input,
+    };
+
+    check.value(0u8);
+    check.value(u8::MAX);
+    check.value(u16::MAX);
+    check.value(u32::MAX);
+    check.value(u64::MAX);
+    check.value(u128::MAX);
+    check.value(i8::MIN);
+    check.value(i16::MIN);
+    check.value(i32::MIN);
+    check.value(-1i64);
+    check.value(i128::MIN);
+    check.value(300u64);
+    check.value(-300i32);
+
+    check.value(true);
+    check.value(false);
+    check.value('z');
+    check.value('é');
+    check.value(1.5f32);
+    check.value(-2.25f64);
+    check.value(());
+    check.value(String::new());
+    check.value("zkVM input".to_string());
+
+    check.value(vec![1u8, 2, 3]);
+    check.value(Vec::<u64>::new());
+    check.value(vec![u32::MAX; 7]);
+    check.value([7u8; 32]);
+    check.value(Some(5u32));
+    check.value(None::<u32>);
+    check.value((1u8, 2u64, "tuple".to_string()));
+    check.value(BTreeMap::from([
+        (1u8, "one".to_string()),
+        (2, "two".to_string()),
+    ]));
+
+    check.value(Unit);
+    check.value(Newtype(42));
+    check.value(Enum::Unit);
+    check.value(Enum::Newtype(-7));
+    check.value(Enum::Tuple(3, true));
+    check.value(Enum::Struct {
+        hash: [0x11; 32],
+        parent: Some(Box::new(Enum::Unit)),
+    });
+    check.value(Block {
+        number: 22_000_000,
+        gas_used: 30_000_000,
+        extra_data: b"ere".to_vec(),
+        transactions: vec![
+            ("transfer".to_string(), Enum::Tuple(1, false)),
+            ("call".to_string(), Enum::Newtype(i64::MAX)),
+        ],
+        balances: BTreeMap::from([(1, -1), (2, i128::MAX)]),
+    });
+
+    check.file();
+}
+
+/// Checks that a proof of `proof_kind` for `input` commits to the same public
+/// values as executing the program, that it verifies to them, and that
+/// [`zkVMVerifier::verify_with_public_values`] accepts them but rejects any
+/// others.
+///
+/// Returns the public values and the proof, for the checks specific to the
+/// backend.
+///
+/// [`zkVMVerifier::verify_with_public_values`]: crate::zkVMVerifier::verify_with_public_values
+pub fn check_prove_verify(
+    zkvm: &impl zkVM,
+    input: &Input,
+    proof_kind: ProofKind,
+) -> (PublicValues, Vec<u8>) {
+    let (public_values, proof, _) = zkvm
+        .prove(input, proof_kind)
+        .unwrap_or_else(|err| panic!("proving a {proof_kind} proof failed: {err:?}"));
+    assert!(!proof.is_empty(), "proof should not be empty");
+
+    let (executed_public_values, _) = zkvm.execute(input).expect("execution should succeed");
+    assert_eq!(executed_public_values, public_values);
+
+    let verified_public_values = zkvm.verify(&proof).expect("proof should verify");
+    assert_eq!(verified_public_values, public_values);
+
+    zkvm.verify_with_public_values(&proof, &public_values)
+        .expect("proof should verify against its own public values");
+
+    let mut unexpected_public_values = public_values.clone();
+    match unexpected_public_values.first_mut() {
+        Some(byte) => *byte ^= 1,
+        None => unexpected_public_values.push(0),
+    }
+    assert!(matches!(
+        zkvm.verify_with_public_values(&proof, &unexpected_public_values),
+        Err(zkVMError::PublicValuesMismatch { .. })
+    ));
+
+    (public_values, proof)
+}
+
+struct Parity<F> {
+    encoding: InputEncoding,
+    guest_input: F,
+}
+
+impl<F, R> Parity<F>
+where
+    F: Fn(&Input) -> R,
+    R: GuestInputReader,
+{
+    /// Writes `value` between raw bytes items, both as is and replayed from
+    /// [`Input::to_bytes`].
+    fn value<T>(&self, value: T)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + Debug + Clone + Send + Sync + 'static,
+    {
+        let mut input = Input::new();
+        input.write_bytes(self.raw_bytes(&[0xab; 3]));
+        input.write(value.clone());
+        input.write(0xdead_beef_u32);
+        input.write(value.clone());
+        input.write_bytes(self.raw_bytes(&[0xcd; 5]));
+
+        let replayed = Input::from_bytes(&input.to_bytes().unwrap()).unwrap();
+        assert!(matches!(
+            replayed.iter().nth(1),
+            Some(InputItem::Encoded(_))
+        ));
+
+        for input in [&input, &replayed] {
+            let mut guest = (self.guest_input)(input);
+            assert_eq!(guest.read_bytes(), [0xab; 3], "{value:?}");
+            assert_eq!(guest.read::<T>(), value);
+            assert_eq!(guest.read::<u32>(), 0xdead_beef, "{value:?}");
+            assert_eq!(guest.read::<T>(), value);
+            assert_eq!(guest.read_bytes(), [0xcd; 5], "{value:?}");
+            assert!(guest.is_empty(), "{value:?}");
+        }
+    }
+
+    /// Writes a file between objects, both as is and replayed from
+    /// [`Input::to_bytes`], which reads it in.
+    fn file(&self) {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("ere-input-parity-{}.bin", std::process::id()));
+        let contents = [1u8, 2, 3, 4, 5, 6, 7];
+        std::fs::write(&path, self.raw_bytes(&contents)).unwrap();
+
+        let mut input = Input::new();
+        input.write(9u8);
+        input.write_file(&path);
+        input.write(10u8);
+        let replayed = Input::from_bytes(&input.to_bytes().unwrap()).unwrap();
+
+        let guests = [(self.guest_input)(&input), (self.guest_input)(&replayed)];
+        std::fs::remove_file(&path).unwrap();
+        for mut guest in guests {
+            assert_eq!(guest.read::<u8>(), 9);
+            assert_eq!(guest.read_bytes(), contents);
+            assert_eq!(guest.read::<u8>(), 10);
+            assert!(guest.is_empty());
+        }
+    }
+
+    /// Jolt takes raw bytes as they are, so they have to be pre-encoded into
+    /// the `Vec<u8>` argument of the `#[jolt::provable]` function.
+    fn raw_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        match self.encoding {
+            InputEncoding::Postcard => PublicValuesEncoding::Postcard.encode(bytes).unwrap(),
+            _ => bytes.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Newtype(u16);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Enum {
+    Unit,
+    Newtype(i64),
+    Tuple(u8, bool),
+    Struct {
+        hash: [u8; 32],
+        parent: Option<Box<Enum>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Block {
+    number: u64,
+    gas_used: u128,
+    extra_data: Vec<u8>,
+    transactions: Vec<(String, Enum)>,
+    balances: BTreeMap<u32, i128>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_prefixed_parity() {
+        check_input_parity(InputEncoding::LengthPrefixedBincode, |input| {
+            LengthPrefixedInput::new(input.encode_length_prefixed().unwrap())
+        });
+    }
+}
@@ -5,11 +5,13 @@ fn fib(n: u32) -> u128 {
     let mut a: u128 = 0;
     let mut b: u128 = 1;
     let mut sum: u128;
+    ere_guest::cycle_region_start("fib_loop");
     for _ in 1..n {
         sum = a + b;
         a = b;
         b = sum;
     }
+    ere_guest::cycle_region_end("fib_loop");
 
-    // b
+    b
 }
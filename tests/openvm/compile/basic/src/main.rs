@@ -1,7 +1,5 @@
-use openvm::io::{read, reveal_u32};
-
 fn main() {
-    let n: u64 = read();
+    let n: u64 = ere_guest::read();
     let mut a: u64 = 0;
     let mut b: u64 = 1;
     for _ in 0..n {
@@ -9,6 +7,7 @@ fn main() {
         a = b;
         b = c;
     }
-    reveal_u32(a as u32, 0);
-    reveal_u32((a >> 32) as u32, 1);
+    openvm::io::println(format!("fib({n}) = {a}"));
+    // Revealed as two `u32` limbs.
+    ere_guest::commit(&a);
 }
@@ -0,0 +1,8 @@
+use openvm::io::{read, reveal_u32};
+
+fn main() {
+    let n: u64 = read();
+    let square = n.wrapping_mul(n);
+    reveal_u32(square as u32, 0);
+    reveal_u32((square >> 32) as u32, 1);
+}
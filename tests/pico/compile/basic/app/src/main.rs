@@ -2,11 +2,10 @@
 
 pico_sdk::entrypoint!(main);
 use fibonacci_lib::{FibonacciData, fibonacci};
-use pico_sdk::io::{commit, read_as};
 
 pub fn main() {
     // Read inputs `n` from the environment
-    let n: u32 = read_as();
+    let n: u32 = ere_guest::read();
 
     let a: u32 = 0;
     let b: u32 = 1;
@@ -22,5 +21,5 @@ pub fn main() {
         b: b_result,
     };
 
-    commit(&result);
+    ere_guest::commit(&result);
 }
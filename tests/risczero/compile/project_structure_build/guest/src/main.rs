@@ -1,13 +1,7 @@
-use risc0_zkvm::guest::env;
-
 fn main() {
-    // TODO: Implement your guest code here
-
     // read the input
-    let input: u32 = env::read();
-
-    // TODO: do something with the input
+    let input: u32 = ere_guest::read();
 
     // write public output to the journal
-    env::commit(&input);
+    ere_guest::commit(&input);
 }
@@ -3,8 +3,13 @@
 sp1_zkvm::entrypoint!(main);
 pub fn main() {
     // Read an input
-    let n = sp1_zkvm::io::read::<u32>();
-    let a = sp1_zkvm::io::read::<u16>() as u32;
+    let n: u32 = ere_guest::read();
+    let a = ere_guest::read::<u16>() as u32;
+    println!("n = {n}, a = {a}");
 
-    sp1_zkvm::io::commit(&((n + a) * 2));
+    ere_guest::cycle_region_start("compute");
+    let output = (n + a) * 2;
+    ere_guest::cycle_region_end("compute");
+
+    ere_guest::commit(&output);
 }